@@ -1,33 +1,95 @@
+use crate::types::money::Currency;
 use crate::types::request::{
-    ActivateAndSignInRequest, ActivationRequest, EmailRequest, RegistrationRequest,
+    ActivateAndSignInRequest, ActivationRequest, CartRequest, ChangePasswordRequest,
+    CheckInRequest, CurbsideCheckInRequest, DeliveryEstimateRequest, Device,
+    DeviceRegistrationRequest, EmailRequest, NotificationPreferencesRequest, OrderRequest,
+    PasswordResetRequest, PaymentMethodRequest, PushTokenRequest, RecoveryRequest,
+    RecoveryVerificationRequest, RegistrationRequest, RestaurantLocationQuery,
+    SubscriptionsRequest, TableServiceCheckInRequest,
 };
 use crate::types::response::{
-    ActivateAndSignInResponse, ActivationResponse, CatalogResponse, ClientResponse,
-    CustomerPointResponse, EmailResponse, LoginRefreshResponse, LoginResponse,
-    OfferDealStackResponse, OfferDetailsResponse, OfferResponse, RegistrationResponse,
-    RestaurantLocationResponse, RestaurantResponse, TokenResponse,
+    read_text, ActivateAndSignInResponse, ActivationResponse, AuthErrorResponse, CatalogResponse,
+    ChangePasswordResponse, ClientResponse, CustomerPointResponse, DeleteAccountResponse,
+    DeliveryEstimateResponse, DeliveryTrackingResponse, DeviceListResponse, DeviceResponse,
+    DisplayCategoriesResponse, EmailResponse, FullRestaurantInformation, LoginRefreshResponse,
+    LoginResponse, LogoutResponse, LoyaltyIdentificationResponse, LoyaltyPromotionsResponse,
+    LoyaltyRedemptionResponse, LoyaltyRewardsResponse, MarketStaticDataResponse, MaxBodySize,
+    NotificationPreferencesResponse, NutritionResponse, Offer, OfferDealStackResponse,
+    OfferDetails, OfferDetailsResponse, OfferImage, OfferLockResponse, OfferResponse,
+    OrderResponse, OrderStatusResponse, OrderTotalResponse, PaymentMethodResponse,
+    ProductOutagesResponse, ProductResponse, PromotionsResponse, PunchCardResponse,
+    PushTokenResponse, ReceiptResponse, ReceivedAt, RecommendationsResponse, RecoveryResponse,
+    RecoveryVerificationResponse, RedemptionAdvice, RedemptionHistoryResponse, RegisteredDevice,
+    RegistrationResponse, RequestedLanguage, RestaurantLocationCoverage,
+    RestaurantLocationResponse, RestaurantResponse, RetainRawBody, StatusResponse,
+    SubscriptionsResponse, TableService, Token, TokenResponse,
 };
-use crate::ClientResult;
-use anyhow::Context;
-use reqwest::Method;
+#[cfg(feature = "metrics")]
+use crate::Metrics;
+use crate::{
+    ClientConfig, ClientError, ClientEvent, ClientResult, Clock, Environment, OfferPipeline,
+    RateLimiter, RegistrationError, RequestSigner, SourceApp, StoreContext,
+};
+use anyhow::{anyhow, Context};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use futures_util::{stream, StreamExt};
+use http::header::{HeaderName, HeaderValue, ACCEPT_LANGUAGE, CONTENT_TYPE};
+use http::HeaderMap;
+use reqwest::{Method, StatusCode, Url};
 use reqwest_middleware::{ClientWithMiddleware, RequestBuilder};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashSet};
 use std::fmt::{Debug, Display};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::broadcast;
 use tracing::instrument;
 use uuid::Uuid;
 
+#[derive(Clone)]
 pub struct ApiClient {
     base_url: String,
     client: ClientWithMiddleware,
     auth_token: Option<String>,
+    refresh_token: Option<String>,
     login_token: Option<String>,
+    login_token_expires_at: Option<DateTime<Utc>>,
     client_id: String,
+    accept_language: String,
+    market: String,
+    header_overrides: HeaderMap,
+    request_body_hook: Option<Arc<RequestBodyHook>>,
+    uuid_generator: Option<Arc<UuidGenerator>>,
+    events: Option<broadcast::Sender<ClientEvent>>,
+    signer: Option<Arc<dyn RequestSigner>>,
+    offer_pipeline: Option<OfferPipeline>,
+    rate_limiter: Option<Arc<dyn RateLimiter>>,
+    image_base_url: String,
+    raw_body_limit: Option<usize>,
+    max_body_size: Option<usize>,
+    endpoint_max_body_sizes: Vec<(String, usize)>,
+    traceparent_provider: Option<Arc<TraceparentProvider>>,
+    clock: Option<Arc<dyn Clock>>,
+    lenient_offers: bool,
+    default_store: Option<StoreContext>,
+    device: Option<Device>,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Arc<Metrics>>,
+    source_app: SourceApp,
 }
 
+type RequestBodyHook = dyn Fn(&Method, &Url, &[u8]) + Send + Sync;
+type UuidGenerator = dyn Fn() -> Uuid + Send + Sync;
+type TraceparentProvider = dyn Fn() -> Option<String> + Send + Sync;
+
 impl Debug for ApiClient {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ApiClient")
             .field("base_url", &self.base_url)
             .field("client", &self.client)
+            .field("accept_language", &self.accept_language)
             .finish()
     }
 }
@@ -38,8 +100,103 @@ impl ApiClient {
             base_url,
             client,
             login_token: None,
+            login_token_expires_at: None,
             auth_token: None,
+            refresh_token: None,
             client_id,
+            accept_language: String::from("en-AU"),
+            market: String::from("AU"),
+            header_overrides: HeaderMap::new(),
+            request_body_hook: None,
+            uuid_generator: None,
+            events: None,
+            signer: None,
+            offer_pipeline: None,
+            rate_limiter: None,
+            image_base_url: Environment::Production.image_base_url(),
+            raw_body_limit: None,
+            max_body_size: None,
+            endpoint_max_body_sizes: Vec::new(),
+            traceparent_provider: None,
+            clock: None,
+            lenient_offers: false,
+            default_store: None,
+            device: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
+            source_app: SourceApp::default(),
+        }
+    }
+
+    pub fn from_config(
+        config: &ClientConfig,
+        client: ClientWithMiddleware,
+    ) -> ClientResult<ApiClient> {
+        let mut api_client =
+            ApiClient::new(config.base_url.clone(), client, config.client_id.clone());
+
+        if let Some(market) = &config.market {
+            api_client.set_market(market);
+        }
+
+        if let Some(accept_language) = &config.accept_language {
+            api_client.set_accept_language(accept_language);
+        }
+
+        for (name, value) in &config.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .with_context(|| format!("invalid header name {name}"))?;
+            let value = HeaderValue::from_str(value)
+                .with_context(|| format!("invalid value for header {name}"))?;
+            api_client.set_header_override(name, value);
+        }
+
+        if let Some(login_token) = &config.login_token {
+            api_client.set_login_token(login_token);
+        }
+
+        if let Some(auth_token) = &config.auth_token {
+            api_client.set_auth_token(auth_token);
+        }
+
+        if let Some(refresh_token) = &config.refresh_token {
+            api_client.set_refresh_token(refresh_token);
+        }
+
+        if let Some(store) = &config.store {
+            api_client.set_default_store(&store.store_id, store.offset);
+        }
+
+        if let Some(source_app) = config.source_app {
+            api_client.set_source_app(source_app);
+        }
+
+        if let Some(device) = &config.device {
+            api_client.set_device(device.clone());
+        }
+
+        Ok(api_client)
+    }
+
+    pub fn config(&self) -> ClientConfig {
+        ClientConfig {
+            base_url: self.base_url.clone(),
+            client_id: self.client_id.clone(),
+            market: Some(self.market.clone()),
+            accept_language: Some(self.accept_language.clone()),
+            device: self.device.clone(),
+            headers: self
+                .header_overrides
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect(),
+            login_token: self.login_token.clone(),
+            auth_token: self.auth_token.clone(),
+            refresh_token: self.refresh_token.clone(),
+            store: self.default_store.clone(),
+            source_app: Some(self.source_app),
         }
     }
 
@@ -51,17 +208,179 @@ impl ApiClient {
             .request(method, format!("{base_url}/{resource}"))
             .header("accept-encoding", "gzip")
             .header("accept-charset", "UTF-8")
-            .header("accept-language", "en-AU")
+            .header("accept-language", &self.accept_language)
             .header("content-type", "application/json; charset=UTF-8")
             .header("mcd-clientid", client_id)
-            .header("mcd-uuid", Self::get_uuid())
-            .header("user-agent", "MCDSDK/29.0.8 (Android; 33; en-AU) GMA/8.6.2")
-            .header("mcd-sourceapp", "GMA")
-            .header("mcd-marketid", "AU")
+            .header("mcd-uuid", self.get_uuid())
+            .header(
+                "user-agent",
+                self.source_app
+                    .user_agent_for(self.device.as_ref())
+                    .as_ref(),
+            )
+            .header("mcd-sourceapp", self.source_app.header_value())
+            .header("mcd-marketid", &self.market)
+    }
+
+    #[instrument(skip_all, fields(correlation_id = tracing::field::Empty))]
+    async fn send(&self, request: RequestBuilder) -> ClientResult<reqwest::Response> {
+        let mut request = request.build()?;
+
+        // wait before signing, a signature made before a long wait can be stale by the time it's sent
+        let resource = request.url().path().to_string();
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.acquire(request.method(), &resource).await?;
+        }
+
+        if let Some(traceparent) = self.traceparent_provider.as_ref().and_then(|p| p()) {
+            if let Ok(traceparent) = HeaderValue::from_str(&traceparent) {
+                request.headers_mut().insert("traceparent", traceparent);
+            }
+        }
+
+        for (name, value) in &self.header_overrides {
+            request.headers_mut().insert(name, value.clone());
+        }
+
+        let language = request
+            .headers()
+            .get(ACCEPT_LANGUAGE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| RequestedLanguage(v.to_string()));
+
+        if let Some(signer) = &self.signer {
+            let body = request.body().and_then(|b| b.as_bytes());
+            let headers = signer.sign(request.method(), request.url().path(), body)?;
+            request.headers_mut().extend(headers);
+        }
+
+        if let Some(hook) = &self.request_body_hook {
+            if let Some(body) = request.body().and_then(|b| b.as_bytes()) {
+                hook(request.method(), request.url(), body);
+            }
+        }
+
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.metrics {
+            metrics.record_request();
+        }
+
+        let response = self.client.execute(request).await;
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.metrics {
+            metrics.record_response(response.as_ref().ok().map(|r| r.status().as_u16()));
+        }
+
+        let mut response = response?;
+        let max_body_size = self.max_body_size_for(&resource);
+        if let Some(correlation_id) = response
+            .headers()
+            .get("x-correlation-id")
+            .and_then(|v| v.to_str().ok())
+        {
+            tracing::Span::current().record("correlation_id", correlation_id);
+        }
+
+        if response.status() == StatusCode::FORBIDDEN {
+            self.emit(ClientEvent::AccountBlocked {
+                status: response.status(),
+                resource,
+            });
+        }
+
+        if let Some(language) = language {
+            response.extensions_mut().insert(language);
+        }
+
+        if let Some(limit) = self.raw_body_limit {
+            response.extensions_mut().insert(RetainRawBody(limit));
+        }
+
+        if let Some(limit) = max_body_size {
+            response.extensions_mut().insert(MaxBodySize(limit));
+        }
+
+        response.extensions_mut().insert(ReceivedAt(self.now()));
+
+        Ok(response)
+    }
+
+    // images are served from a cdn, so none of the api headers or signing apply
+    async fn offer_image_response(&self, image_base_name: &str) -> ClientResult<reqwest::Response> {
+        let image_base_url = &self.image_base_url;
+        let request = self
+            .client
+            .get(format!("{image_base_url}/{image_base_name}"))
+            .build()?;
+
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter
+                .acquire(request.method(), request.url().path())
+                .await?;
+        }
+
+        Ok(self.client.execute(request).await?.error_for_status()?)
     }
 
-    fn get_uuid() -> String {
-        Uuid::new_v4().as_hyphenated().to_string()
+    async fn fetch_offer_image(&self, image_base_name: &str) -> ClientResult<OfferImage> {
+        let response = self.offer_image_response(image_base_name).await?;
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string());
+
+        Ok(OfferImage {
+            image_base_name: image_base_name.to_string(),
+            content_type,
+            bytes: response.bytes().await?,
+        })
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        self.clock
+            .as_ref()
+            .map_or_else(Utc::now, |clock| clock.now())
+    }
+
+    fn emit(&self, event: ClientEvent) {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.metrics {
+            match event {
+                ClientEvent::TokenRefreshed => metrics.record_token_refresh(),
+                ClientEvent::AccountBlocked { .. } => metrics.record_account_blocked(),
+                _ => {}
+            }
+        }
+
+        if let Some(events) = &self.events {
+            // no receivers is fine, nobody is listening
+            let _ = events.send(event);
+        }
+    }
+
+    fn next_uuid(&self) -> Uuid {
+        match &self.uuid_generator {
+            Some(generator) => generator(),
+            None => Uuid::new_v4(),
+        }
+    }
+
+    fn get_uuid(&self) -> String {
+        self.next_uuid().as_hyphenated().to_string()
+    }
+
+    // ids come from the uuid generator and the timezone from the market, utc for unknown markets
+    pub fn new_android_device(&self, api_level: u32) -> Option<Device> {
+        Device::android(api_level, &self.next_uuid(), self.device_timezone())
+    }
+
+    pub fn new_ios_device(&self, version: &str) -> Device {
+        Device::ios(version, &self.next_uuid(), self.device_timezone())
+    }
+
+    fn device_timezone(&self) -> &'static str {
+        Device::timezone_for_market(&self.market).unwrap_or("Etc/UTC")
     }
 
     pub fn set_login_token<S>(&mut self, login_token: &S)
@@ -69,6 +388,38 @@ impl ApiClient {
         S: Display + ?Sized + Debug,
     {
         self.login_token = Some(login_token.to_string());
+        self.login_token_expires_at = None;
+    }
+
+    pub fn set_login_token_from(&mut self, token: &Token) {
+        self.login_token = Some(token.token.clone());
+        self.login_token_expires_at = Some(token.expires_at(self.now()));
+    }
+
+    pub fn login_token_expires_at(&self) -> Option<DateTime<Utc>> {
+        self.login_token_expires_at
+    }
+
+    // fetches a new login token if there is none or the current one is about to expire
+    #[instrument(skip(client_secret))]
+    pub async fn ensure_login_token<A>(&mut self, client_secret: &A) -> ClientResult<()>
+    where
+        A: Display + ?Sized + Debug,
+    {
+        let margin = chrono::Duration::seconds(60);
+        let valid = match (&self.login_token, self.login_token_expires_at) {
+            (Some(_), Some(expires_at)) => self.now() + margin < expires_at,
+            // set manually, nothing to compare against
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+
+        if !valid {
+            let response = self.security_auth_token(client_secret).await?;
+            self.set_login_token_from(&response.body.response);
+        }
+
+        Ok(())
     }
 
     pub fn set_auth_token<S>(&mut self, auth_token: &S)
@@ -78,6 +429,217 @@ impl ApiClient {
         self.auth_token = Some(auth_token.to_string());
     }
 
+    pub fn set_refresh_token<S>(&mut self, refresh_token: &S)
+    where
+        S: Display + ?Sized + Debug,
+    {
+        self.refresh_token = Some(refresh_token.to_string());
+    }
+
+    pub fn refresh_token(&self) -> Option<&str> {
+        self.refresh_token.as_deref()
+    }
+
+    pub fn subscribe(&mut self) -> broadcast::Receiver<ClientEvent> {
+        self.events
+            .get_or_insert_with(|| broadcast::channel(64).0)
+            .subscribe()
+    }
+
+    pub fn set_default_store<S>(&mut self, store_id: &S, offset: i64)
+    where
+        S: Display + ?Sized + Debug,
+    {
+        self.default_store = Some(StoreContext {
+            store_id: store_id.to_string(),
+            offset,
+        });
+    }
+
+    pub fn default_store(&self) -> Option<&StoreContext> {
+        self.default_store.as_ref()
+    }
+
+    // the device this client's account is registered with, kept so it survives a config round trip
+    pub fn set_device(&mut self, device: Device) {
+        self.device = Some(device);
+    }
+
+    pub fn device(&self) -> Option<&Device> {
+        self.device.as_ref()
+    }
+
+    fn require_default_store(&self) -> ClientResult<&StoreContext> {
+        Ok(self
+            .default_store
+            .as_ref()
+            .context("no default store set")?)
+    }
+
+    pub fn market(&self) -> &str {
+        &self.market
+    }
+
+    // None for markets without a known currency
+    pub fn currency(&self) -> Option<Currency> {
+        Currency::for_market(&self.market)
+    }
+
+    pub fn set_market<S>(&mut self, market: &S)
+    where
+        S: Display + ?Sized + Debug,
+    {
+        self.market = market.to_string();
+    }
+
+    // replaces the default header of the same name on every request
+    pub fn set_header_override(&mut self, name: HeaderName, value: HeaderValue) {
+        self.header_overrides.insert(name, value);
+    }
+
+    pub fn set_accept_language<S>(&mut self, accept_language: &S)
+    where
+        S: Display + ?Sized + Debug,
+    {
+        self.accept_language = accept_language.to_string();
+    }
+
+    // offers and menu names are localised, this allows fetching another language for a single call
+    pub fn with_accept_language<S>(&self, accept_language: &S) -> ApiClient
+    where
+        S: Display + ?Sized + Debug,
+    {
+        let mut client = self.clone();
+        client.set_accept_language(accept_language);
+        client
+    }
+
+    pub fn set_source_app(&mut self, source_app: SourceApp) {
+        self.source_app = source_app;
+    }
+
+    // same as with_accept_language, for calls that only work as the web ordering client
+    pub fn with_source_app(&self, source_app: SourceApp) -> ApiClient {
+        let mut client = self.clone();
+        client.set_source_app(source_app);
+        client
+    }
+
+    pub fn set_request_signer<S>(&mut self, signer: S)
+    where
+        S: RequestSigner + 'static,
+    {
+        self.signer = Some(Arc::new(signer));
+    }
+
+    // the same metrics can be shared by many clients
+    #[cfg(feature = "metrics")]
+    pub fn set_metrics(&mut self, metrics: Arc<Metrics>) {
+        self.metrics = Some(metrics);
+    }
+
+    pub fn set_clock<C>(&mut self, clock: C)
+    where
+        C: Clock + 'static,
+    {
+        self.clock = Some(Arc::new(clock));
+    }
+
+    // when enabled, offers that fail to parse are dropped from get_offers and listed in
+    // `ClientResponse::skipped` instead of failing the whole response
+    pub fn set_lenient_offer_parsing(&mut self, lenient: bool) {
+        self.lenient_offers = lenient;
+    }
+
+    // applied to the offers returned by get_offers
+    pub fn set_offer_pipeline(&mut self, pipeline: OfferPipeline) {
+        self.offer_pipeline = Some(pipeline);
+    }
+
+    pub fn set_rate_limiter<R>(&mut self, rate_limiter: R)
+    where
+        R: RateLimiter + 'static,
+    {
+        self.rate_limiter = Some(Arc::new(rate_limiter));
+    }
+
+    pub fn set_environment(&mut self, environment: &Environment) {
+        self.base_url = environment.base_url();
+        self.image_base_url = environment.image_base_url();
+    }
+
+    pub fn set_image_base_url<S>(&mut self, image_base_url: &S)
+    where
+        S: Display + ?Sized + Debug,
+    {
+        self.image_base_url = image_base_url.to_string();
+    }
+
+    // keeps the raw bytes of response bodies up to `limit` bytes on ClientResponse::raw_body
+    pub fn set_raw_body_limit(&mut self, limit: Option<usize>) {
+        self.raw_body_limit = limit;
+    }
+
+    // responses over `limit` bytes fail with ClientError::ResponseTooLarge
+    pub fn set_max_body_size(&mut self, limit: Option<usize>) {
+        self.max_body_size = limit;
+    }
+
+    // overrides set_max_body_size for paths starting with `path_prefix`, e.g.
+    // "/exp/v1/menu/catalog". the longest matching prefix wins
+    pub fn set_endpoint_max_body_size<S>(&mut self, path_prefix: &S, limit: usize)
+    where
+        S: Display + ?Sized + Debug,
+    {
+        let path_prefix = path_prefix.to_string();
+        self.endpoint_max_body_sizes
+            .retain(|(prefix, _)| *prefix != path_prefix);
+        self.endpoint_max_body_sizes.push((path_prefix, limit));
+    }
+
+    fn max_body_size_for(&self, path: &str) -> Option<usize> {
+        self.endpoint_max_body_sizes
+            .iter()
+            .filter(|(prefix, _)| path.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, limit)| *limit)
+            .or(self.max_body_size)
+    }
+
+    // sends the returned w3c traceparent on every request, e.g. taken from the current
+    // opentelemetry context. returning None skips the header for that request
+    pub fn set_traceparent_provider<F>(&mut self, provider: F)
+    where
+        F: Fn() -> Option<String> + Send + Sync + 'static,
+    {
+        self.traceparent_provider = Some(Arc::new(provider));
+    }
+
+    // for when there is no tracing context to propagate, starts a new trace per request
+    pub fn enable_traceparent(&mut self) {
+        self.set_traceparent_provider(|| {
+            let trace_id = Uuid::new_v4().simple().to_string();
+            let span_id = &Uuid::new_v4().simple().to_string()[..16];
+            Some(format!("00-{trace_id}-{span_id}-01"))
+        });
+    }
+
+    // replaces the random v4 uuid sent as mcd-uuid, e.g. a seeded generator for deterministic tests
+    pub fn set_uuid_generator<F>(&mut self, generator: F)
+    where
+        F: Fn() -> Uuid + Send + Sync + 'static,
+    {
+        self.uuid_generator = Some(Arc::new(generator));
+    }
+
+    // called with the exact serialized body of every request that has one, before it is sent
+    pub fn set_request_body_hook<F>(&mut self, hook: F)
+    where
+        F: Fn(&Method, &Url, &[u8]) + Send + Sync + 'static,
+    {
+        self.request_body_hook = Some(Arc::new(hook));
+    }
+
     // POST https://ap-prod.api.mcd.com/v1/security/auth/token
     #[instrument]
     pub async fn security_auth_token<A>(
@@ -98,10 +660,76 @@ impl ApiClient {
                 "application/x-www-form-urlencoded; charset=UTF-8",
             );
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
         tracing::debug!("raw response: {:?}", response);
 
-        ClientResponse::from_response(response).await
+        let status = response.status();
+        if let Err(e) = response.error_for_status_ref() {
+            if matches!(status, StatusCode::BAD_REQUEST | StatusCode::UNAUTHORIZED) {
+                let body = read_text(response).await?;
+                return match serde_json::from_str::<AuthErrorResponse>(&body) {
+                    Ok(body) if body.is_invalid_client() => {
+                        Err(ClientError::InvalidClientCredentials {
+                            status,
+                            message: body.message(),
+                        })
+                    }
+                    _ => Err(e.into()),
+                };
+            }
+        }
+
+        // rejected credentials can also come back with a success status
+        let response = ClientResponse::<Value>::from_response(response).await?;
+        if response.body.get("response").is_none_or(|r| r.is_null()) {
+            if let Ok(body) = serde_json::from_value::<AuthErrorResponse>(response.body.clone()) {
+                if body.is_invalid_client() {
+                    return Err(ClientError::InvalidClientCredentials {
+                        status: response.status,
+                        message: body.message(),
+                    });
+                }
+            }
+        }
+
+        response.parse_body()
+    }
+
+    // leaked client ids get revoked, so try each id/secret pair in order and keep the first accepted
+    #[instrument(skip(credentials))]
+    pub async fn security_auth_token_with_fallback<A, B>(
+        &mut self,
+        credentials: &[(A, B)],
+    ) -> ClientResult<ClientResponse<TokenResponse>>
+    where
+        A: Display + Debug,
+        B: Display + Debug,
+    {
+        let original_client_id = self.client_id.clone();
+        let mut last_error = None;
+
+        for (client_id, client_secret) in credentials {
+            self.client_id = client_id.to_string();
+
+            match self.security_auth_token(client_secret).await {
+                Ok(response) => return Ok(response),
+                // 403 and 429 are blocks and throttling, another id won't help and would burn it
+                Err(e)
+                    if matches!(e, ClientError::InvalidClientCredentials { .. })
+                        || e.status() == Some(StatusCode::UNAUTHORIZED) =>
+                {
+                    tracing::warn!("client id {client_id} was rejected, trying next");
+                    last_error = Some(e);
+                }
+                Err(e) => {
+                    self.client_id = original_client_id;
+                    return Err(e);
+                }
+            }
+        }
+
+        self.client_id = original_client_id;
+        Err(last_error.unwrap_or_else(|| anyhow!("no client credentials provided").into()))
     }
 
     // POST https://ap-prod.api.mcd.com/exp/v1/customer/registration
@@ -122,9 +750,24 @@ impl ApiClient {
             .bearer_auth(token)
             .json(&request);
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
         tracing::debug!("raw response: {:?}", response);
 
+        // 401 and 403 are auth failures, not a problem with the registration itself
+        let status = response.status();
+        if let Err(e) = response.error_for_status_ref() {
+            if matches!(status, StatusCode::BAD_REQUEST | StatusCode::CONFLICT) {
+                let body = read_text(response).await?;
+                return match serde_json::from_str::<StatusResponse>(&body) {
+                    Ok(body) => Err(ClientError::Registration {
+                        status,
+                        error: RegistrationError::from_status(status, body.status),
+                    }),
+                    Err(_) => Err(e.into()),
+                };
+            }
+        }
+
         ClientResponse::from_response(response).await
     }
 
@@ -146,7 +789,7 @@ impl ApiClient {
             .bearer_auth(token)
             .json(&request);
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
         tracing::debug!("raw response: {:?}", response);
 
         ClientResponse::from_response(response).await
@@ -170,7 +813,7 @@ impl ApiClient {
             .bearer_auth(token)
             .json(&request);
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
         tracing::debug!("raw response: {:?}", response);
 
         ClientResponse::from_response(response).await
@@ -194,7 +837,7 @@ impl ApiClient {
             .bearer_auth(token)
             .json(&request);
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
         tracing::debug!("raw response: {:?}", response);
 
         ClientResponse::from_response(response).await
@@ -213,63 +856,417 @@ impl ApiClient {
             .bearer_auth(token)
             .json(&request);
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
         tracing::debug!("raw response: {:?}", response);
 
         ClientResponse::from_response(response).await
     }
 
-    // POST https://ap-prod.api.mcd.com/exp/v1/customer/login
+    // cheap check before registering, doesn't need sensor data. the existing account flow is
+    // only accepted for registered emails, unknown ones come back as a 404
+    #[instrument]
+    pub async fn is_email_registered<A, B>(&self, email: &A, device_id: &B) -> ClientResult<bool>
+    where
+        A: Display + ?Sized + Debug,
+        B: Display + ?Sized + Debug,
+    {
+        let request = EmailRequest {
+            customer_identifier: email.to_string(),
+            device_id: device_id.to_string(),
+            registration_type: "EXISTING".to_string(),
+        };
+
+        match self.identity_email(&request).await {
+            Ok(_) => Ok(true),
+            Err(e) if e.status() == Some(StatusCode::NOT_FOUND) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    // POST https://ap-prod.api.mcd.com/exp/v1/customer/recovery
     #[instrument(skip(sensor_data))]
-    pub async fn customer_login<A, B, C, D>(
+    pub async fn request_customer_recovery<A>(
         &self,
-        login_username: &A,
-        login_password: &B,
-        sensor_data: &C,
-        device_id: &D,
-    ) -> ClientResult<ClientResponse<LoginResponse>>
+        request: &RecoveryRequest,
+        sensor_data: &A,
+    ) -> ClientResult<ClientResponse<RecoveryResponse>>
     where
         A: Display + ?Sized + Debug,
-        B: Display + ?Sized + Debug,
-        C: Display + ?Sized + Debug,
-        D: Display + ?Sized + Debug,
     {
         let token = self.login_token.as_ref().context("no login token set")?;
 
-        let credentials = serde_json::json!({
-            "credentials": {
-                "loginUsername": login_username.to_string(),
-                "password": login_password.to_string(),
-                "type": "email"
-            },
-            "deviceId": device_id.to_string()
-        });
-
         let request = self
-            .get_default_request("exp/v1/customer/login", Method::POST)
-            .bearer_auth(token)
+            .get_default_request("exp/v1/customer/recovery", Method::POST)
             .header("x-acf-sensor-data", sensor_data.to_string())
-            .json(&credentials);
+            .bearer_auth(token)
+            .json(&request);
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
         tracing::debug!("raw response: {:?}", response);
 
         ClientResponse::from_response(response).await
     }
 
-    // GET https://ap-prod.api.mcd.com/exp/v1/offers?distance=10000&exclude=14&latitude=-32.0117&longitude=115.8845&optOuts=&timezoneOffsetInMinutes=480
-    #[instrument]
-    pub async fn get_offers<A, B, C, D, E>(
+    // PUT https://ap-prod.api.mcd.com/exp/v1/customer/recovery/verify
+    #[instrument(skip(sensor_data))]
+    pub async fn verify_customer_recovery<A>(
         &self,
-        distance: &A,
-        latitude: &B,
-        longitude: &C,
-        opt_outs: &D,
-        timezone_offset_in_minutes: &E,
-    ) -> ClientResult<ClientResponse<OfferResponse>>
+        request: &RecoveryVerificationRequest,
+        sensor_data: &A,
+    ) -> ClientResult<ClientResponse<RecoveryVerificationResponse>>
     where
         A: Display + ?Sized + Debug,
-        B: Display + ?Sized + Debug,
+    {
+        let token = self.login_token.as_ref().context("no login token set")?;
+
+        let request = self
+            .get_default_request("exp/v1/customer/recovery/verify", Method::PUT)
+            .header("x-acf-sensor-data", sensor_data.to_string())
+            .bearer_auth(token)
+            .json(&request);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // PUT https://ap-prod.api.mcd.com/exp/v1/customer/recovery/password
+    #[instrument(skip(sensor_data))]
+    pub async fn reset_customer_password<A>(
+        &self,
+        request: &PasswordResetRequest,
+        sensor_data: &A,
+    ) -> ClientResult<ClientResponse<RecoveryResponse>>
+    where
+        A: Display + ?Sized + Debug,
+    {
+        let token = self.login_token.as_ref().context("no login token set")?;
+
+        let request = self
+            .get_default_request("exp/v1/customer/recovery/password", Method::PUT)
+            .header("x-acf-sensor-data", sensor_data.to_string())
+            .bearer_auth(token)
+            .json(&request);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // POST https://ap-prod.api.mcd.com/exp/v1/customer/login
+    #[instrument(skip(sensor_data))]
+    pub async fn customer_login<A, B, C, D>(
+        &self,
+        login_username: &A,
+        login_password: &B,
+        sensor_data: &C,
+        device_id: &D,
+    ) -> ClientResult<ClientResponse<LoginResponse>>
+    where
+        A: Display + ?Sized + Debug,
+        B: Display + ?Sized + Debug,
+        C: Display + ?Sized + Debug,
+        D: Display + ?Sized + Debug,
+    {
+        let token = self.login_token.as_ref().context("no login token set")?;
+
+        let credentials = serde_json::json!({
+            "credentials": {
+                "loginUsername": login_username.to_string(),
+                "password": login_password.to_string(),
+                "type": "email"
+            },
+            "deviceId": device_id.to_string()
+        });
+
+        let request = self
+            .get_default_request("exp/v1/customer/login", Method::POST)
+            .bearer_auth(token)
+            .header("x-acf-sensor-data", sensor_data.to_string())
+            .json(&credentials);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // PUT https://ap-prod.api.mcd.com/exp/v1/customer/password
+    #[instrument(skip(old_password, new_password, sensor_data))]
+    pub async fn change_password<A, B, C>(
+        &self,
+        old_password: &A,
+        new_password: &B,
+        sensor_data: &C,
+    ) -> ClientResult<ClientResponse<ChangePasswordResponse>>
+    where
+        A: Display + ?Sized + Debug,
+        B: Display + ?Sized + Debug,
+        C: Display + ?Sized + Debug,
+    {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let body = ChangePasswordRequest {
+            old_password: old_password.to_string(),
+            new_password: new_password.to_string(),
+        };
+
+        let request = self
+            .get_default_request("exp/v1/customer/password", Method::PUT)
+            .header("x-acf-sensor-data", sensor_data.to_string())
+            .bearer_auth(token)
+            .json(&body);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // POST https://ap-prod.api.mcd.com/exp/v1/customer/device
+    #[instrument(skip(sensor_data))]
+    pub async fn register_customer_device<A>(
+        &self,
+        request: &DeviceRegistrationRequest,
+        sensor_data: &A,
+    ) -> ClientResult<ClientResponse<DeviceResponse>>
+    where
+        A: Display + ?Sized + Debug,
+    {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+
+        let request = self
+            .get_default_request("exp/v1/customer/device", Method::POST)
+            .header("x-acf-sensor-data", sensor_data.to_string())
+            .bearer_auth(token)
+            .json(&request);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // GET https://ap-prod.api.mcd.com/exp/v1/customer/devices
+    #[instrument]
+    pub async fn get_customer_devices(&self) -> ClientResult<ClientResponse<DeviceListResponse>> {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let request = self
+            .get_default_request("exp/v1/customer/devices", Method::GET)
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // adopts the tokens taken from an existing app install. the access token is checked by listing
+    // the account's devices, which must include `device_id`, otherwise the previous tokens are kept
+    #[instrument(skip(access_token, refresh_token))]
+    pub async fn import_app_session<A, B, C>(
+        &mut self,
+        access_token: &A,
+        refresh_token: &B,
+        device_id: &C,
+    ) -> ClientResult<RegisteredDevice>
+    where
+        A: Display + ?Sized + Debug,
+        B: Display + ?Sized + Debug,
+        C: Display + ?Sized + Debug,
+    {
+        let previous = self.auth_token.replace(access_token.to_string());
+        let previous_refresh = self.refresh_token.replace(refresh_token.to_string());
+        let device_id = device_id.to_string();
+
+        let device = match self.get_customer_devices().await {
+            Ok(response) => response
+                .body
+                .response
+                .and_then(|r| r.devices.into_iter().find(|d| d.device_id == device_id))
+                .ok_or_else(|| {
+                    anyhow!("device {device_id} is not registered to this account").into()
+                }),
+            Err(e) => Err(e),
+        };
+
+        if device.is_err() {
+            self.auth_token = previous;
+            self.refresh_token = previous_refresh;
+        }
+
+        device
+    }
+
+    // DELETE https://ap-prod.api.mcd.com/exp/v1/customer/device/0f4d5e1c2b3a4d5e
+    #[instrument]
+    pub async fn remove_customer_device<S>(
+        &self,
+        device_id: &S,
+    ) -> ClientResult<ClientResponse<DeviceResponse>>
+    where
+        S: Display + ?Sized + Debug,
+    {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let request = self
+            .get_default_request(
+                format!("exp/v1/customer/device/{device_id}").as_str(),
+                Method::DELETE,
+            )
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // POST https://ap-prod.api.mcd.com/exp/v1/customer/device/pushtoken
+    #[instrument]
+    pub async fn register_push_token(
+        &self,
+        request: &PushTokenRequest,
+    ) -> ClientResult<ClientResponse<PushTokenResponse>> {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+
+        let request = self
+            .get_default_request("exp/v1/customer/device/pushtoken", Method::POST)
+            .bearer_auth(token)
+            .json(&request);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // DELETE https://ap-prod.api.mcd.com/exp/v1/customer/device/pushtoken
+    #[instrument]
+    pub async fn unregister_push_token(
+        &self,
+        request: &PushTokenRequest,
+    ) -> ClientResult<ClientResponse<PushTokenResponse>> {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+
+        let request = self
+            .get_default_request("exp/v1/customer/device/pushtoken", Method::DELETE)
+            .bearer_auth(token)
+            .json(&request);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // GET https://ap-prod.api.mcd.com/exp/v1/customer/preferences
+    #[instrument]
+    pub async fn get_notification_preferences(
+        &self,
+    ) -> ClientResult<ClientResponse<NotificationPreferencesResponse>> {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let request = self
+            .get_default_request("exp/v1/customer/preferences", Method::GET)
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // PUT https://ap-prod.api.mcd.com/exp/v1/customer/preferences
+    #[instrument]
+    pub async fn update_notification_preferences(
+        &self,
+        request: &NotificationPreferencesRequest,
+    ) -> ClientResult<ClientResponse<NotificationPreferencesResponse>> {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+
+        let request = self
+            .get_default_request("exp/v1/customer/preferences", Method::PUT)
+            .bearer_auth(token)
+            .json(&request);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // PUT https://ap-prod.api.mcd.com/exp/v1/customer/subscriptions
+    #[instrument]
+    pub async fn update_customer_subscriptions(
+        &self,
+        request: &SubscriptionsRequest,
+    ) -> ClientResult<ClientResponse<SubscriptionsResponse>> {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+
+        let request = self
+            .get_default_request("exp/v1/customer/subscriptions", Method::PUT)
+            .bearer_auth(token)
+            .json(&request);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // POST https://ap-prod.api.mcd.com/exp/v1/customer/payment/methods
+    #[instrument(skip(sensor_data))]
+    pub async fn add_payment_method<A>(
+        &self,
+        request: &PaymentMethodRequest,
+        sensor_data: &A,
+    ) -> ClientResult<ClientResponse<PaymentMethodResponse>>
+    where
+        A: Display + ?Sized + Debug,
+    {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+
+        let request = self
+            .get_default_request("exp/v1/customer/payment/methods", Method::POST)
+            .header("x-acf-sensor-data", sensor_data.to_string())
+            .bearer_auth(token)
+            .json(&request);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // DELETE https://ap-prod.api.mcd.com/exp/v1/customer
+    #[instrument]
+    pub async fn delete_customer_account(
+        &self,
+    ) -> ClientResult<ClientResponse<DeleteAccountResponse>> {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let request = self
+            .get_default_request("exp/v1/customer", Method::DELETE)
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // GET https://ap-prod.api.mcd.com/exp/v1/offers?distance=10000&exclude=14&latitude=-32.0117&longitude=115.8845&optOuts=&timezoneOffsetInMinutes=480
+    #[instrument]
+    pub async fn get_offers<A, B, C, D, E>(
+        &self,
+        distance: &A,
+        latitude: &B,
+        longitude: &C,
+        opt_outs: &D,
+        timezone_offset_in_minutes: &E,
+    ) -> ClientResult<ClientResponse<OfferResponse>>
+    where
+        A: Display + ?Sized + Debug,
+        B: Display + ?Sized + Debug,
         C: Display + ?Sized + Debug,
         D: Display + ?Sized + Debug,
         E: Display + ?Sized + Debug,
@@ -287,280 +1284,1370 @@ impl ApiClient {
 
         let token = self.auth_token.as_ref().context("no auth token set")?;
         let request = self
-            .get_default_request("exp/v1/offers", Method::GET)
-            .query(&params)
+            .get_default_request("exp/v1/offers", Method::GET)
+            .query(&params)
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        let mut response = if self.lenient_offers {
+            ClientResponse::<Value>::from_response(response)
+                .await?
+                .into_offers_lenient()?
+        } else {
+            ClientResponse::<OfferResponse>::from_response(response).await?
+        };
+
+        if let (Some(pipeline), Some(list)) = (&self.offer_pipeline, &mut response.body.response) {
+            pipeline.apply(&mut list.offers);
+        }
+
+        Ok(response)
+    }
+
+    // GET https://ap-prod.api.mcd.com/exp/v1/personalization/recommendations?offset=480&storeId=951488
+    #[instrument]
+    pub async fn get_recommendations<A, B>(
+        &self,
+        offset: &A,
+        store_id: &B,
+    ) -> ClientResult<ClientResponse<RecommendationsResponse>>
+    where
+        A: Display + ?Sized + Debug,
+        B: Display + ?Sized + Debug,
+    {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let params = Vec::from([
+            (String::from("offset"), offset.to_string()),
+            (String::from("storeId"), store_id.to_string()),
+        ]);
+
+        let request = self
+            .get_default_request("exp/v1/personalization/recommendations", Method::GET)
+            .query(&params)
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // GET https://au-prod-us-cds-oceofferimages.s3.amazonaws.com/oce3-au-prod/offers/OFFER_IMAGE.png
+    #[instrument]
+    pub async fn get_offer_image<S>(&self, image_base_name: &S) -> ClientResult<OfferImage>
+    where
+        S: Display + ?Sized + Debug,
+    {
+        self.fetch_offer_image(&image_base_name.to_string()).await
+    }
+
+    // downloads the images of `offers` with at most `concurrency` requests in flight, each image
+    // is only fetched once. failures don't stop the rest and are returned with the image name
+    #[instrument(skip(offers, sink))]
+    pub async fn prefetch_offer_images<F>(
+        &self,
+        offers: &[Offer],
+        concurrency: usize,
+        mut sink: F,
+    ) -> Vec<(String, ClientError)>
+    where
+        F: FnMut(OfferImage),
+    {
+        let image_base_names = offers
+            .iter()
+            .map(|o| o.image_base_name.as_str())
+            .collect::<BTreeSet<_>>();
+
+        let mut downloads = stream::iter(image_base_names)
+            .map(|name| async move { (name, self.fetch_offer_image(name).await) })
+            .buffer_unordered(concurrency.max(1));
+
+        let mut failures = Vec::new();
+        while let Some((name, result)) = downloads.next().await {
+            match result {
+                Ok(image) => sink(image),
+                Err(e) => failures.push((name.to_string(), e)),
+            }
+        }
+
+        failures
+    }
+
+    // images already in `directory` are skipped, the rest are streamed straight to disk
+    #[instrument(skip(offers))]
+    pub async fn prefetch_offer_images_to_dir(
+        &self,
+        offers: &[Offer],
+        concurrency: usize,
+        directory: &Path,
+    ) -> Vec<(String, ClientError)> {
+        let image_base_names = offers
+            .iter()
+            .map(|o| o.image_base_name.as_str())
+            .collect::<BTreeSet<_>>();
+
+        let mut failures = Vec::new();
+        let mut missing = Vec::new();
+        for name in image_base_names {
+            // the name comes from the api, so only a plain file name is allowed
+            let Some(file_name) = safe_file_name(name) else {
+                failures.push((
+                    name.to_string(),
+                    anyhow!("invalid image name {name}").into(),
+                ));
+                continue;
+            };
+
+            let path = directory.join(file_name);
+            match tokio::fs::try_exists(&path).await {
+                Ok(true) => {}
+                Ok(false) => missing.push((name, path)),
+                Err(e) => failures.push((name.to_string(), anyhow::Error::from(e).into())),
+            }
+        }
+
+        let mut downloads = stream::iter(missing)
+            .map(|(name, path)| async move { (name, self.download_offer_image(name, &path).await) })
+            .buffer_unordered(concurrency.max(1));
+
+        while let Some((name, result)) = downloads.next().await {
+            if let Err(e) = result {
+                failures.push((name.to_string(), e));
+            }
+        }
+
+        failures
+    }
+
+    // written to a temporary file first, so an interrupted download never looks cached
+    async fn download_offer_image(&self, image_base_name: &str, path: &Path) -> ClientResult<()> {
+        let mut response = self.offer_image_response(image_base_name).await?;
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".part");
+        let partial = PathBuf::from(partial);
+
+        let result = async {
+            let mut file = tokio::fs::File::create(&partial).await?;
+            while let Some(chunk) = response.chunk().await? {
+                file.write_all(&chunk).await?;
+            }
+
+            file.flush().await?;
+            tokio::fs::rename(&partial, path).await?;
+            Ok::<_, anyhow::Error>(())
+        }
+        .await;
+
+        if result.is_err() {
+            let _ = tokio::fs::remove_file(&partial).await;
+        }
+
+        Ok(result?)
+    }
+
+    // GET https://ap-prod.api.mcd.com/exp/v1/restaurant/location?distance=20&filter=summary&latitude=-32.0117&longitude=115.8845
+    #[instrument]
+    pub async fn restaurant_location<A, B, C, D>(
+        &self,
+        distance: &A,
+        latitude: &B,
+        longitude: &C,
+        filter: &D,
+    ) -> ClientResult<ClientResponse<RestaurantLocationResponse>>
+    where
+        A: Display + ?Sized + Debug,
+        B: Display + ?Sized + Debug,
+        C: Display + ?Sized + Debug,
+        D: Display + ?Sized + Debug,
+    {
+        let params = Vec::from([
+            (String::from("distance"), distance.to_string()),
+            (String::from("latitude"), latitude.to_string()),
+            (String::from("longitude"), longitude.to_string()),
+            (String::from("filter"), filter.to_string()),
+        ]);
+
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let request = self
+            .get_default_request("exp/v1/restaurant/location", Method::GET)
+            .query(&params)
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // GET https://ap-prod.api.mcd.com/exp/v1/restaurant/location?distance=20&filter=summary&latitude=-32.0117&longitude=115.8845&storeUniqueIdType=NSN&facilities=DRIVETHRU
+    #[instrument]
+    pub async fn restaurant_location_query(
+        &self,
+        query: &RestaurantLocationQuery,
+    ) -> ClientResult<ClientResponse<RestaurantLocationResponse>> {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let request = self
+            .get_default_request("exp/v1/restaurant/location", Method::GET)
+            .query(query)
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // the api has no text search, so the restaurants found by `query` are filtered locally
+    #[instrument]
+    pub async fn search_restaurants<S>(
+        &self,
+        query: &RestaurantLocationQuery,
+        search: &S,
+    ) -> ClientResult<ClientResponse<RestaurantLocationResponse>>
+    where
+        S: Display + ?Sized + Debug,
+    {
+        let search = search.to_string();
+        let mut response = self.restaurant_location_query(query).await?;
+        if let Some(list) = response.body.response.as_mut() {
+            list.restaurants.retain(|r| r.matches(&search));
+        }
+
+        Ok(response)
+    }
+
+    // the api silently truncates large searches, so any area that comes back with `result_cap`
+    // restaurants is split into four quadrants and searched again, up to `max_depth` times
+    #[instrument]
+    pub async fn restaurant_location_subdivided<A>(
+        &self,
+        distance: f64,
+        latitude: f64,
+        longitude: f64,
+        filter: &A,
+        result_cap: usize,
+        max_depth: usize,
+    ) -> ClientResult<RestaurantLocationCoverage>
+    where
+        A: Display + ?Sized + Debug,
+    {
+        // distance is in km
+        const KM_PER_DEGREE: f64 = 111.32;
+
+        let mut pending = Vec::from([(distance, latitude, longitude, 0)]);
+        let mut restaurants = Vec::new();
+        let mut seen = HashSet::new();
+        let mut complete = true;
+
+        while let Some((distance, latitude, longitude, depth)) = pending.pop() {
+            let response = self
+                .restaurant_location(&distance, &latitude, &longitude, filter)
+                .await?;
+            let found = response
+                .body
+                .response
+                .map(|r| r.restaurants)
+                .unwrap_or_default();
+
+            if found.len() >= result_cap {
+                if depth < max_depth {
+                    let offset = distance / 2.0;
+                    let lat_offset = offset / KM_PER_DEGREE;
+                    let long_offset = offset / (KM_PER_DEGREE * latitude.to_radians().cos());
+                    let sub_distance = distance / std::f64::consts::SQRT_2;
+
+                    for (lat_sign, long_sign) in
+                        [(1.0, 1.0), (1.0, -1.0), (-1.0, 1.0), (-1.0, -1.0)]
+                    {
+                        pending.push((
+                            sub_distance,
+                            latitude + lat_sign * lat_offset,
+                            longitude + long_sign * long_offset,
+                            depth + 1,
+                        ));
+                    }
+                } else {
+                    complete = false;
+                }
+            }
+
+            for restaurant in found {
+                if seen.insert(restaurant.national_store_number) {
+                    restaurants.push(restaurant);
+                }
+            }
+        }
+
+        Ok(RestaurantLocationCoverage {
+            restaurants,
+            complete,
+        })
+    }
+
+    // GET https://ap-prod.api.mcd.com/exp/v1/offers/details/166870
+    #[instrument]
+    pub async fn offer_details<S>(
+        &self,
+        offer_proposition_id: &S,
+    ) -> ClientResult<ClientResponse<OfferDetailsResponse>>
+    where
+        S: Display + ?Sized + Debug,
+    {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+
+        let request = self
+            .get_default_request(
+                format!("exp/v1/offers/details/{offer_proposition_id}").as_str(),
+                Method::GET,
+            )
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // GET https://ap-prod.api.mcd.com/exp/v1/offers/details/166870/restaurants?distance=20&latitude=-32.0117&longitude=115.8845
+    #[instrument]
+    pub async fn get_offer_restaurants<A, B, C, D>(
+        &self,
+        offer_proposition_id: &A,
+        latitude: &B,
+        longitude: &C,
+        distance: &D,
+    ) -> ClientResult<ClientResponse<RestaurantLocationResponse>>
+    where
+        A: Display + ?Sized + Debug,
+        B: Display + ?Sized + Debug,
+        C: Display + ?Sized + Debug,
+        D: Display + ?Sized + Debug,
+    {
+        let params = Vec::from([
+            (String::from("distance"), distance.to_string()),
+            (String::from("latitude"), latitude.to_string()),
+            (String::from("longitude"), longitude.to_string()),
+        ]);
+
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let request = self
+            .get_default_request(
+                format!("exp/v1/offers/details/{offer_proposition_id}/restaurants").as_str(),
+                Method::GET,
+            )
+            .query(&params)
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // PUT https://ap-prod.api.mcd.com/exp/v1/offers/1139347703/lock
+    #[instrument]
+    pub async fn lock_offer<S>(
+        &self,
+        offer_id: &S,
+    ) -> ClientResult<ClientResponse<OfferLockResponse>>
+    where
+        S: Display + ?Sized + Debug,
+    {
+        self.set_offer_lock(offer_id, "lock").await
+    }
+
+    // PUT https://ap-prod.api.mcd.com/exp/v1/offers/1139347703/unlock
+    #[instrument]
+    pub async fn unlock_offer<S>(
+        &self,
+        offer_id: &S,
+    ) -> ClientResult<ClientResponse<OfferLockResponse>>
+    where
+        S: Display + ?Sized + Debug,
+    {
+        self.set_offer_lock(offer_id, "unlock").await
+    }
+
+    async fn set_offer_lock<S>(
+        &self,
+        offer_id: &S,
+        action: &str,
+    ) -> ClientResult<ClientResponse<OfferLockResponse>>
+    where
+        S: Display + ?Sized + Debug,
+    {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+
+        let request = self
+            .get_default_request(
+                format!("exp/v1/offers/{offer_id}/{action}").as_str(),
+                Method::PUT,
+            )
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // GET https://ap-prod.api.mcd.com/exp/v1/offers/punchcard/166870
+    #[instrument]
+    pub async fn get_punch_card<S>(
+        &self,
+        offer_proposition_id: &S,
+    ) -> ClientResult<ClientResponse<PunchCardResponse>>
+    where
+        S: Display + ?Sized + Debug,
+    {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+
+        let request = self
+            .get_default_request(
+                format!("exp/v1/offers/punchcard/{offer_proposition_id}").as_str(),
+                Method::GET,
+            )
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // GET https://ap-prod.api.mcd.com/exp/v1/offers/redemption/history
+    #[instrument]
+    pub async fn get_redemption_history(
+        &self,
+    ) -> ClientResult<ClientResponse<RedemptionHistoryResponse>> {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let request = self
+            .get_default_request("exp/v1/offers/redemption/history", Method::GET)
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // GET https://ap-prod.api.mcd.com/exp/v1/offers/dealstack?offset=480&storeId=951488
+    #[instrument]
+    pub async fn get_offers_dealstack<A, B>(
+        &self,
+        offset: &A,
+        store_id: &B,
+    ) -> ClientResult<ClientResponse<OfferDealStackResponse>>
+    where
+        A: Display + ?Sized + Debug,
+        B: Display + ?Sized + Debug,
+    {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let params = Vec::from([
+            (String::from("offset"), offset.to_string()),
+            (String::from("storeId"), store_id.to_string()),
+        ]);
+
+        let request = self
+            .get_default_request("exp/v1/offers/dealstack", Method::GET)
+            .query(&params)
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // POST https://ap-prod.api.mcd.com/exp/v1/offers/dealstack/166870?offerId=1139347703&offset=480&storeId=951488
+    #[instrument]
+    pub async fn add_to_offers_dealstack<A, B, C>(
+        &self,
+        offer_id: &A,
+        offset: &B,
+        store_id: &C,
+    ) -> ClientResult<ClientResponse<OfferDealStackResponse>>
+    where
+        A: Display + ?Sized + Debug,
+        B: Display + ?Sized + Debug,
+        C: Display + ?Sized + Debug,
+    {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let params = Vec::from([
+            (String::from("offset"), offset.to_string()),
+            (String::from("storeId"), store_id.to_string()),
+        ]);
+
+        let request = self
+            .get_default_request(
+                format!("exp/v1/offers/dealstack/{offer_id}").as_str(),
+                Method::POST,
+            )
+            .query(&params)
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        let response = ClientResponse::from_response(response).await?;
+        self.emit(ClientEvent::OfferRedeemed {
+            offer_id: offer_id.to_string(),
+            store_id: store_id.to_string(),
+        });
+
+        Ok(response)
+    }
+
+    // checks the store's offer configuration before adding, the api only tells you the stack is full after the fact
+    #[instrument(skip(restaurant))]
+    pub async fn add_to_offers_dealstack_checked<A, B, C>(
+        &self,
+        offer_id: &A,
+        offset: &B,
+        store_id: &C,
+        restaurant: &FullRestaurantInformation,
+    ) -> ClientResult<ClientResponse<OfferDealStackResponse>>
+    where
+        A: Display + ?Sized + Debug,
+        B: Display + ?Sized + Debug,
+        C: Display + ?Sized + Debug,
+    {
+        if let Some(limit) = restaurant.max_stackable_offers() {
+            let current = self.get_offers_dealstack(offset, store_id).await?;
+            let stacked = current
+                .body
+                .response
+                .and_then(|r| r.deal_stack)
+                .map_or(0, |d| d.len());
+
+            if stacked >= limit {
+                return Err(ClientError::DealStackLimitReached { limit });
+            }
+        }
+
+        self.add_to_offers_dealstack(offer_id, offset, store_id)
+            .await
+    }
+
+    // DELETE https://ap-prod.api.mcd.com/exp/v1/offers/dealstack/offer/166870?offerId=1139347703&offset=480&storeId=951488
+    #[instrument]
+    pub async fn remove_from_offers_dealstack<A, B, C, D>(
+        &self,
+        offer_id: &A,
+        offer_proposition_id: &B,
+        offset: &C,
+        store_id: &D,
+    ) -> ClientResult<ClientResponse<OfferDealStackResponse>>
+    where
+        A: Display + ?Sized + Debug,
+        B: Display + ?Sized + Debug,
+        C: Display + ?Sized + Debug,
+        D: Display + ?Sized + Debug,
+    {
+        // the app sends a body, but this request works without it
+        // but we're pretending to be the app :)
+        let body = serde_json::json!(
+            {
+                "storeId": store_id.to_string(),
+                "offerId": offer_id.to_string().parse::<i64>()?,
+                "offset": offset.to_string().parse::<i64>()?,
+            }
+        );
+
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let params = Vec::from([
+            (String::from("offerId"), offer_id.to_string()),
+            (String::from("offset"), offset.to_string()),
+            (String::from("storeId"), store_id.to_string()),
+        ]);
+
+        let request = self
+            .get_default_request(
+                format!("exp/v1/offers/dealstack/offer/{offer_proposition_id}").as_str(),
+                Method::DELETE,
+            )
+            .json(&body)
+            .query(&params)
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // there is no bulk endpoint, so each stacked offer is removed individually
+    #[instrument]
+    pub async fn clear_offers_dealstack<A, B>(
+        &self,
+        offset: &A,
+        store_id: &B,
+    ) -> ClientResult<ClientResponse<OfferDealStackResponse>>
+    where
+        A: Display + ?Sized + Debug,
+        B: Display + ?Sized + Debug,
+    {
+        let current = self.get_offers_dealstack(offset, store_id).await?;
+        let deal_stack = current
+            .body
+            .response
+            .and_then(|r| r.deal_stack)
+            .unwrap_or_default();
+
+        for deal in deal_stack {
+            self.remove_from_offers_dealstack(
+                &deal.offer_id,
+                &deal.offer_proposition_id,
+                offset,
+                store_id,
+            )
+            .await?;
+        }
+
+        self.get_offers_dealstack(offset, store_id).await
+    }
+
+    // the *_default_store variants use the store set with set_default_store, the methods
+    // above can still be called directly to target any other store
+    #[instrument]
+    pub async fn get_default_store_offers_dealstack(
+        &self,
+    ) -> ClientResult<ClientResponse<OfferDealStackResponse>> {
+        let store = self.require_default_store()?;
+        self.get_offers_dealstack(&store.offset, &store.store_id)
+            .await
+    }
+
+    #[instrument]
+    pub async fn add_to_default_store_offers_dealstack<A>(
+        &self,
+        offer_id: &A,
+    ) -> ClientResult<ClientResponse<OfferDealStackResponse>>
+    where
+        A: Display + ?Sized + Debug,
+    {
+        let store = self.require_default_store()?;
+        self.add_to_offers_dealstack(offer_id, &store.offset, &store.store_id)
+            .await
+    }
+
+    #[instrument]
+    pub async fn remove_from_default_store_offers_dealstack<A, B>(
+        &self,
+        offer_id: &A,
+        offer_proposition_id: &B,
+    ) -> ClientResult<ClientResponse<OfferDealStackResponse>>
+    where
+        A: Display + ?Sized + Debug,
+        B: Display + ?Sized + Debug,
+    {
+        let store = self.require_default_store()?;
+        self.remove_from_offers_dealstack(
+            offer_id,
+            offer_proposition_id,
+            &store.offset,
+            &store.store_id,
+        )
+        .await
+    }
+
+    #[instrument]
+    pub async fn clear_default_store_offers_dealstack(
+        &self,
+    ) -> ClientResult<ClientResponse<OfferDealStackResponse>> {
+        let store = self.require_default_store()?;
+        self.clear_offers_dealstack(&store.offset, &store.store_id)
+            .await
+    }
+
+    // POST https://ap-prod.api.mcd.com/exp/v1/customer/login/refresh
+    #[instrument]
+    pub async fn customer_login_refresh<S>(
+        &self,
+        refresh_token: &S,
+    ) -> ClientResult<ClientResponse<LoginRefreshResponse>>
+    where
+        S: Display + ?Sized + Debug,
+    {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let body = serde_json::json!({ "refreshToken": refresh_token.to_string() });
+
+        let request = self
+            .get_default_request("exp/v1/customer/login/refresh", Method::POST)
+            .bearer_auth(token)
+            .json(&body);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        let response = ClientResponse::from_response(response).await?;
+        self.emit(ClientEvent::TokenRefreshed);
+
+        Ok(response)
+    }
+
+    // refreshes with the stored refresh token and keeps the new pair
+    #[instrument]
+    pub async fn refresh_session(&mut self) -> ClientResult<ClientResponse<LoginRefreshResponse>> {
+        let refresh_token = self.refresh_token.clone().context("no refresh token set")?;
+        let response = self.customer_login_refresh(&refresh_token).await?;
+
+        if let Some(tokens) = &response.body.response {
+            self.auth_token = Some(tokens.access_token.clone());
+            self.refresh_token = Some(tokens.refresh_token.clone());
+        }
+
+        Ok(response)
+    }
+
+    // POST https://ap-prod.api.mcd.com/exp/v1/customer/logout
+    // the session is revoked server side, so both tokens are cleared once it succeeds
+    #[instrument(skip(refresh_token))]
+    pub async fn customer_logout<S>(
+        &mut self,
+        refresh_token: &S,
+    ) -> ClientResult<ClientResponse<LogoutResponse>>
+    where
+        S: Display + ?Sized + Debug,
+    {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let body = serde_json::json!({ "refreshToken": refresh_token.to_string() });
+
+        let request = self
+            .get_default_request("exp/v1/customer/logout", Method::POST)
+            .bearer_auth(token)
+            .json(&body);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        let response = ClientResponse::from_response(response).await?;
+        self.auth_token = None;
+        self.refresh_token = None;
+
+        Ok(response)
+    }
+
+    // GET https://ap-prod.api.mcd.com/exp/v1/loyalty/customer/points
+    #[instrument]
+    pub async fn get_customer_points(&self) -> ClientResult<ClientResponse<CustomerPointResponse>> {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let request = self
+            .get_default_request("exp/v1/loyalty/customer/points", Method::GET)
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // GET https://ap-prod.api.mcd.com/exp/v1/loyalty/customer/identification
+    #[instrument]
+    pub async fn get_loyalty_identification(
+        &self,
+    ) -> ClientResult<ClientResponse<LoyaltyIdentificationResponse>> {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let request = self
+            .get_default_request("exp/v1/loyalty/customer/identification", Method::GET)
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // GET https://ap-prod.api.mcd.com/exp/v1/loyalty/rewards
+    #[instrument]
+    pub async fn get_loyalty_rewards(
+        &self,
+    ) -> ClientResult<ClientResponse<LoyaltyRewardsResponse>> {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let request = self
+            .get_default_request("exp/v1/loyalty/rewards", Method::GET)
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // `offers` are the customer's current offer details, rewards they already cover are flagged
+    #[instrument(skip(offers))]
+    pub async fn get_redemption_advice(
+        &self,
+        offers: &[OfferDetails],
+    ) -> ClientResult<Vec<RedemptionAdvice>> {
+        let points = self.get_customer_points().await?;
+        let rewards = self.get_loyalty_rewards().await?;
+
+        Ok(rewards
+            .body
+            .response
+            .map(|r| r.advise(points.body.response.total_points, offers))
+            .unwrap_or_default())
+    }
+
+    // GET https://ap-prod.api.mcd.com/exp/v1/loyalty/promotions
+    #[instrument]
+    pub async fn get_loyalty_promotions(
+        &self,
+    ) -> ClientResult<ClientResponse<LoyaltyPromotionsResponse>> {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let request = self
+            .get_default_request("exp/v1/loyalty/promotions", Method::GET)
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // GET https://ap-prod.api.mcd.com/exp/v1/promotions?storeId=951488
+    #[instrument]
+    pub async fn get_promotions<A>(
+        &self,
+        store_id: &A,
+    ) -> ClientResult<ClientResponse<PromotionsResponse>>
+    where
+        A: Display + ?Sized + Debug,
+    {
+        let params = Vec::from([(String::from("storeId"), store_id.to_string())]);
+
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let request = self
+            .get_default_request("exp/v1/promotions", Method::GET)
+            .query(&params)
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // POST https://ap-prod.api.mcd.com/exp/v1/loyalty/rewards/redeem/4518?offset=480&storeId=951488
+    #[instrument]
+    pub async fn redeem_loyalty_reward<A, B, C>(
+        &self,
+        reward_id: &A,
+        offset: &B,
+        store_id: &C,
+    ) -> ClientResult<ClientResponse<LoyaltyRedemptionResponse>>
+    where
+        A: Display + ?Sized + Debug,
+        B: Display + ?Sized + Debug,
+        C: Display + ?Sized + Debug,
+    {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let params = Vec::from([
+            (String::from("offset"), offset.to_string()),
+            (String::from("storeId"), store_id.to_string()),
+        ]);
+
+        let request = self
+            .get_default_request(
+                format!("exp/v1/loyalty/rewards/redeem/{reward_id}").as_str(),
+                Method::POST,
+            )
+            .query(&params)
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        let response = ClientResponse::from_response(response).await?;
+        self.emit(ClientEvent::RewardRedeemed {
+            reward_id: reward_id.to_string(),
+            store_id: store_id.to_string(),
+        });
+
+        Ok(response)
+    }
+
+    // GET https://ap-prod.api.mcd.com/exp/v1/menu/catalog/AU/950442?filter=summary
+    #[instrument]
+    pub async fn get_menu_catalog<A, B, C>(
+        &self,
+        country_code: &A,
+        store_id: &B,
+        filter: &C,
+    ) -> ClientResult<ClientResponse<CatalogResponse>>
+    where
+        A: Display + ?Sized + Debug,
+        B: Display + ?Sized + Debug,
+        C: Display + ?Sized + Debug,
+    {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let params = Vec::from([(String::from("filter"), filter.to_string())]);
+        let request = self
+            .get_default_request(
+                format!("exp/v1/menu/catalog/{}/{}", country_code, store_id).as_str(),
+                Method::GET,
+            )
+            .query(&params)
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // GET https://ap-prod.api.mcd.com/exp/v1/menu/categories/AU/950442
+    #[instrument]
+    pub async fn get_display_categories<A, B>(
+        &self,
+        country_code: &A,
+        store_id: &B,
+    ) -> ClientResult<ClientResponse<DisplayCategoriesResponse>>
+    where
+        A: Display + ?Sized + Debug,
+        B: Display + ?Sized + Debug,
+    {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let request = self
+            .get_default_request(
+                format!("exp/v1/menu/categories/{country_code}/{store_id}").as_str(),
+                Method::GET,
+            )
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // GET https://ap-prod.api.mcd.com/exp/v1/menu/product/950442/2101
+    #[instrument]
+    pub async fn get_product<A, B>(
+        &self,
+        store_id: &A,
+        product_code: &B,
+    ) -> ClientResult<ClientResponse<ProductResponse>>
+    where
+        A: Display + ?Sized + Debug,
+        B: Display + ?Sized + Debug,
+    {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let request = self
+            .get_default_request(
+                format!("exp/v1/menu/product/{store_id}/{product_code}").as_str(),
+                Method::GET,
+            )
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // GET https://ap-prod.api.mcd.com/exp/v1/menu/nutrition/2101
+    #[instrument]
+    pub async fn get_product_nutrition<A>(
+        &self,
+        product_code: &A,
+    ) -> ClientResult<ClientResponse<NutritionResponse>>
+    where
+        A: Display + ?Sized + Debug,
+    {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let request = self
+            .get_default_request(
+                format!("exp/v1/menu/nutrition/{product_code}").as_str(),
+                Method::GET,
+            )
             .bearer_auth(token);
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
         tracing::debug!("raw response: {:?}", response);
 
         ClientResponse::from_response(response).await
     }
 
-    // GET https://ap-prod.api.mcd.com/exp/v1/restaurant/location?distance=20&filter=summary&latitude=-32.0117&longitude=115.8845
+    // GET https://ap-prod.api.mcd.com/exp/v1/menu/nutrition/recipe/3145
     #[instrument]
-    pub async fn restaurant_location<A, B, C, D>(
+    pub async fn get_recipe_nutrition<A>(
         &self,
-        distance: &A,
-        latitude: &B,
-        longitude: &C,
-        filter: &D,
-    ) -> ClientResult<ClientResponse<RestaurantLocationResponse>>
+        recipe_id: &A,
+    ) -> ClientResult<ClientResponse<NutritionResponse>>
     where
         A: Display + ?Sized + Debug,
-        B: Display + ?Sized + Debug,
-        C: Display + ?Sized + Debug,
-        D: Display + ?Sized + Debug,
     {
-        let params = Vec::from([
-            (String::from("distance"), distance.to_string()),
-            (String::from("latitude"), latitude.to_string()),
-            (String::from("longitude"), longitude.to_string()),
-            (String::from("filter"), filter.to_string()),
-        ]);
-
         let token = self.auth_token.as_ref().context("no auth token set")?;
         let request = self
-            .get_default_request("exp/v1/restaurant/location", Method::GET)
-            .query(&params)
+            .get_default_request(
+                format!("exp/v1/menu/nutrition/recipe/{recipe_id}").as_str(),
+                Method::GET,
+            )
             .bearer_auth(token);
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
         tracing::debug!("raw response: {:?}", response);
 
         ClientResponse::from_response(response).await
     }
 
-    // GET https://ap-prod.api.mcd.com/exp/v1/offers/details/166870
+    // GET https://ap-prod.api.mcd.com/exp/v1/market/AU/staticdata
     #[instrument]
-    pub async fn offer_details<S>(
+    pub async fn get_market_static_data<A>(
         &self,
-        offer_proposition_id: &S,
-    ) -> ClientResult<ClientResponse<OfferDetailsResponse>>
+        country_code: &A,
+    ) -> ClientResult<ClientResponse<MarketStaticDataResponse>>
     where
-        S: Display + ?Sized + Debug,
+        A: Display + ?Sized + Debug,
     {
         let token = self.auth_token.as_ref().context("no auth token set")?;
-
         let request = self
             .get_default_request(
-                format!("exp/v1/offers/details/{offer_proposition_id}").as_str(),
+                format!("exp/v1/market/{country_code}/staticdata").as_str(),
                 Method::GET,
             )
             .bearer_auth(token);
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
         tracing::debug!("raw response: {:?}", response);
 
         ClientResponse::from_response(response).await
     }
 
-    // GET https://ap-prod.api.mcd.com/exp/v1/offers/dealstack?offset=480&storeId=951488
+    // same as get_menu_catalog, for the client's configured market
     #[instrument]
-    pub async fn get_offers_dealstack<A, B>(
+    pub async fn get_catalog<A, B>(
         &self,
-        offset: &A,
-        store_id: &B,
-    ) -> ClientResult<ClientResponse<OfferDealStackResponse>>
+        store_id: &A,
+        filter: &B,
+    ) -> ClientResult<ClientResponse<CatalogResponse>>
+    where
+        A: Display + ?Sized + Debug,
+        B: Display + ?Sized + Debug,
+    {
+        self.get_menu_catalog(&self.market, store_id, filter).await
+    }
+
+    // GET https://ap-prod.api.mcd.com/exp/v1/restaurant/951094?filter=full&storeUniqueIdType=NSN
+    #[instrument]
+    pub async fn get_restaurant<A, B, C>(
+        &self,
+        store_id: &A,
+        filter: &B,
+        store_unique_id_type: &C,
+    ) -> ClientResult<ClientResponse<RestaurantResponse>>
     where
         A: Display + ?Sized + Debug,
         B: Display + ?Sized + Debug,
+        C: Display + ?Sized + Debug,
     {
         let token = self.auth_token.as_ref().context("no auth token set")?;
         let params = Vec::from([
-            (String::from("offset"), offset.to_string()),
-            (String::from("storeId"), store_id.to_string()),
+            (String::from("filter"), filter.to_string()),
+            (
+                String::from("storeUniqueIdType"),
+                store_unique_id_type.to_string(),
+            ),
         ]);
-
         let request = self
-            .get_default_request("exp/v1/offers/dealstack", Method::GET)
+            .get_default_request(
+                format!("exp/v1/restaurant/{}", store_id).as_str(),
+                Method::GET,
+            )
             .query(&params)
             .bearer_auth(token);
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
         tracing::debug!("raw response: {:?}", response);
 
         ClientResponse::from_response(response).await
     }
 
-    // POST https://ap-prod.api.mcd.com/exp/v1/offers/dealstack/166870?offerId=1139347703&offset=480&storeId=951488
+    // GET https://ap-prod.api.mcd.com/exp/v1/restaurant/951094/outages
     #[instrument]
-    pub async fn add_to_offers_dealstack<A, B, C>(
+    pub async fn get_product_outages<A>(
         &self,
-        offer_id: &A,
-        offset: &B,
-        store_id: &C,
-    ) -> ClientResult<ClientResponse<OfferDealStackResponse>>
+        store_id: &A,
+    ) -> ClientResult<ClientResponse<ProductOutagesResponse>>
     where
         A: Display + ?Sized + Debug,
-        B: Display + ?Sized + Debug,
-        C: Display + ?Sized + Debug,
     {
         let token = self.auth_token.as_ref().context("no auth token set")?;
-        let params = Vec::from([
-            (String::from("offset"), offset.to_string()),
-            (String::from("storeId"), store_id.to_string()),
-        ]);
-
         let request = self
             .get_default_request(
-                format!("exp/v1/offers/dealstack/{offer_id}").as_str(),
-                Method::POST,
+                format!("exp/v1/restaurant/{store_id}/outages").as_str(),
+                Method::GET,
             )
-            .query(&params)
             .bearer_auth(token);
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
         tracing::debug!("raw response: {:?}", response);
 
         ClientResponse::from_response(response).await
     }
 
-    // DELETE https://ap-prod.api.mcd.com/exp/v1/offers/dealstack/offer/166870?offerId=1139347703&offset=480&storeId=951488
+    // POST https://ap-prod.api.mcd.com/exp/v1/order/totalize
     #[instrument]
-    pub async fn remove_from_offers_dealstack<A, B, C, D>(
+    pub async fn totalize_order(
         &self,
-        offer_id: &A,
-        offer_proposition_id: &B,
-        offset: &C,
-        store_id: &D,
-    ) -> ClientResult<ClientResponse<OfferDealStackResponse>>
+        cart: &CartRequest,
+    ) -> ClientResult<ClientResponse<OrderTotalResponse>> {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let request = self
+            .get_default_request("exp/v1/order/totalize", Method::POST)
+            .bearer_auth(token)
+            .json(cart);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // POST https://ap-prod.api.mcd.com/exp/v1/order
+    #[instrument]
+    pub async fn place_order(
+        &self,
+        order: &OrderRequest,
+    ) -> ClientResult<ClientResponse<OrderResponse>> {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let request = self
+            .get_default_request("exp/v1/order", Method::POST)
+            .bearer_auth(token)
+            .json(order);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // `pickup_time` should be one of the slots from get_pickup_slots
+    #[instrument]
+    pub async fn place_scheduled_order(
+        &self,
+        order: &OrderRequest,
+        pickup_time: NaiveDateTime,
+    ) -> ClientResult<ClientResponse<OrderResponse>> {
+        let mut order = order.clone();
+        order.scheduled_pickup_time = Some(pickup_time.format("%Y-%m-%dT%H:%M:%S").to_string());
+
+        self.place_order(&order).await
+    }
+
+    // `now` should be the store's local time, see Order::pickup_slots
+    #[instrument]
+    pub async fn get_pickup_slots<A>(
+        &self,
+        store_id: &A,
+        date: NaiveDate,
+        now: NaiveDateTime,
+        interval_minutes: i64,
+    ) -> ClientResult<Vec<NaiveDateTime>>
     where
         A: Display + ?Sized + Debug,
-        B: Display + ?Sized + Debug,
-        C: Display + ?Sized + Debug,
-        D: Display + ?Sized + Debug,
     {
-        // the app sends a body, but this request works without it
-        // but we're pretending to be the app :)
-        let body = serde_json::json!(
-            {
-                "storeId": store_id.to_string(),
-                "offerId": offer_id.to_string().parse::<i64>()?,
-                "offset": offset.to_string().parse::<i64>()?,
-            }
-        );
+        let restaurant = self.get_restaurant(store_id, "full", "NSN").await?;
+        let order = restaurant
+            .body
+            .response
+            .map(|r| r.restaurant.order)
+            .context("no restaurant in response")?;
+
+        Ok(order.pickup_slots(date, now, interval_minutes))
+    }
 
+    // GET https://ap-prod.api.mcd.com/exp/v1/order/123456789/status
+    #[instrument]
+    pub async fn get_order_status<A>(
+        &self,
+        order_id: &A,
+    ) -> ClientResult<ClientResponse<OrderStatusResponse>>
+    where
+        A: Display + ?Sized + Debug,
+    {
         let token = self.auth_token.as_ref().context("no auth token set")?;
-        let params = Vec::from([
-            (String::from("offerId"), offer_id.to_string()),
-            (String::from("offset"), offset.to_string()),
-            (String::from("storeId"), store_id.to_string()),
-        ]);
-
         let request = self
             .get_default_request(
-                format!("exp/v1/offers/dealstack/offer/{offer_proposition_id}").as_str(),
-                Method::DELETE,
+                format!("exp/v1/order/{order_id}/status").as_str(),
+                Method::GET,
             )
-            .json(&body)
-            .query(&params)
             .bearer_auth(token);
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
         tracing::debug!("raw response: {:?}", response);
 
         ClientResponse::from_response(response).await
     }
 
-    // POST https://ap-prod.api.mcd.com/exp/v1/customer/login/refresh
+    // GET https://ap-prod.api.mcd.com/exp/v1/order/123456789/receipt
     #[instrument]
-    pub async fn customer_login_refresh<S>(
+    pub async fn get_order_receipt<A>(
         &self,
-        refresh_token: &S,
-    ) -> ClientResult<ClientResponse<LoginRefreshResponse>>
+        order_id: &A,
+    ) -> ClientResult<ClientResponse<ReceiptResponse>>
     where
-        S: Display + ?Sized + Debug,
+        A: Display + ?Sized + Debug,
     {
         let token = self.auth_token.as_ref().context("no auth token set")?;
-        let body = serde_json::json!({ "refreshToken": refresh_token.to_string() });
-
         let request = self
-            .get_default_request("exp/v1/customer/login/refresh", Method::POST)
-            .bearer_auth(token)
-            .json(&body);
+            .get_default_request(
+                format!("exp/v1/order/{order_id}/receipt").as_str(),
+                Method::GET,
+            )
+            .bearer_auth(token);
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
         tracing::debug!("raw response: {:?}", response);
 
         ClientResponse::from_response(response).await
     }
 
-    // GET https://ap-prod.api.mcd.com/exp/v1/loyalty/customer/points
+    // POST https://ap-prod.api.mcd.com/exp/v1/delivery/estimate
     #[instrument]
-    pub async fn get_customer_points(&self) -> ClientResult<ClientResponse<CustomerPointResponse>> {
+    pub async fn get_delivery_estimate(
+        &self,
+        request: &DeliveryEstimateRequest,
+    ) -> ClientResult<ClientResponse<DeliveryEstimateResponse>> {
         let token = self.auth_token.as_ref().context("no auth token set")?;
         let request = self
-            .get_default_request("exp/v1/loyalty/customer/points", Method::GET)
-            .bearer_auth(token);
+            .get_default_request("exp/v1/delivery/estimate", Method::POST)
+            .bearer_auth(token)
+            .json(request);
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
         tracing::debug!("raw response: {:?}", response);
 
         ClientResponse::from_response(response).await
     }
 
-    // GET https://ap-prod.api.mcd.com/exp/v1/menu/catalog/AU/950442?filter=summary
+    // GET https://ap-prod.api.mcd.com/exp/v1/delivery/order/123456789/tracking
     #[instrument]
-    pub async fn get_menu_catalog<A, B, C>(
+    pub async fn get_delivery_tracking<A>(
         &self,
-        country_code: &A,
-        store_id: &B,
-        filter: &C,
-    ) -> ClientResult<ClientResponse<CatalogResponse>>
+        order_id: &A,
+    ) -> ClientResult<ClientResponse<DeliveryTrackingResponse>>
     where
         A: Display + ?Sized + Debug,
-        B: Display + ?Sized + Debug,
-        C: Display + ?Sized + Debug,
     {
         let token = self.auth_token.as_ref().context("no auth token set")?;
-        let params = Vec::from([(String::from("filter"), filter.to_string())]);
         let request = self
             .get_default_request(
-                format!("exp/v1/menu/catalog/{}/{}", country_code, store_id).as_str(),
+                format!("exp/v1/delivery/order/{order_id}/tracking").as_str(),
                 Method::GET,
             )
-            .query(&params)
             .bearer_auth(token);
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
         tracing::debug!("raw response: {:?}", response);
 
         ClientResponse::from_response(response).await
     }
 
-    // GET https://ap-prod.api.mcd.com/exp/v1/restaurant/951094?filter=full&storeUniqueIdType=NSN
+    // DELETE https://ap-prod.api.mcd.com/exp/v1/order/123456789
     #[instrument]
-    pub async fn get_restaurant<A, B, C>(
+    pub async fn cancel_order<A>(
         &self,
-        store_id: &A,
-        filter: &B,
-        store_unique_id_type: &C,
-    ) -> ClientResult<ClientResponse<RestaurantResponse>>
+        order_id: &A,
+    ) -> ClientResult<ClientResponse<OrderStatusResponse>>
+    where
+        A: Display + ?Sized + Debug,
+    {
+        let token = self.auth_token.as_ref().context("no auth token set")?;
+        let request = self
+            .get_default_request(format!("exp/v1/order/{order_id}").as_str(), Method::DELETE)
+            .bearer_auth(token);
+
+        let response = self.send(request).await?;
+        tracing::debug!("raw response: {:?}", response);
+
+        ClientResponse::from_response(response).await
+    }
+
+    // POST https://ap-prod.api.mcd.com/exp/v1/order/123456789/checkin
+    #[instrument]
+    pub async fn check_in_order<A>(
+        &self,
+        order_id: &A,
+        check_in: &CheckInRequest,
+    ) -> ClientResult<ClientResponse<OrderStatusResponse>>
+    where
+        A: Display + ?Sized + Debug,
+    {
+        self.post_check_in(order_id, check_in).await
+    }
+
+    // the table number is checked against the store's configuration before anything is sent
+    #[instrument(skip(table_service))]
+    pub async fn check_in_order_table_service<A>(
+        &self,
+        order_id: &A,
+        table_number: i64,
+        table_service: &TableService,
+    ) -> ClientResult<ClientResponse<OrderStatusResponse>>
+    where
+        A: Display + ?Sized + Debug,
+    {
+        let range = table_service.table_number_range();
+        if !range.contains(&table_number) {
+            return Err(ClientError::TableNumberOutOfRange {
+                table_number,
+                min: *range.start(),
+                max: *range.end(),
+            });
+        }
+
+        self.post_check_in(order_id, &TableServiceCheckInRequest::new(table_number))
+            .await
+    }
+
+    #[instrument]
+    pub async fn check_in_order_curbside<A, B>(
+        &self,
+        order_id: &A,
+        parking_bay_number: &B,
+    ) -> ClientResult<ClientResponse<OrderStatusResponse>>
     where
         A: Display + ?Sized + Debug,
         B: Display + ?Sized + Debug,
-        C: Display + ?Sized + Debug,
+    {
+        let check_in = CurbsideCheckInRequest::new(&parking_bay_number.to_string());
+        self.post_check_in(order_id, &check_in).await
+    }
+
+    async fn post_check_in<A, B>(
+        &self,
+        order_id: &A,
+        check_in: &B,
+    ) -> ClientResult<ClientResponse<OrderStatusResponse>>
+    where
+        A: Display + ?Sized + Debug,
+        B: Serialize + Debug,
     {
         let token = self.auth_token.as_ref().context("no auth token set")?;
-        let params = Vec::from([
-            (String::from("filter"), filter.to_string()),
-            (
-                String::from("storeUniqueIdType"),
-                store_unique_id_type.to_string(),
-            ),
-        ]);
         let request = self
             .get_default_request(
-                format!("exp/v1/restaurant/{}", store_id).as_str(),
-                Method::GET,
+                format!("exp/v1/order/{order_id}/checkin").as_str(),
+                Method::POST,
             )
-            .query(&params)
-            .bearer_auth(token);
+            .bearer_auth(token)
+            .json(check_in);
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
         tracing::debug!("raw response: {:?}", response);
 
         ClientResponse::from_response(response).await
     }
 }
+
+fn safe_file_name(name: &str) -> Option<&std::ffi::OsStr> {
+    if name.is_empty() || name.contains(['/', '\\']) || name == "." || name == ".." {
+        return None;
+    }
+
+    Path::new(name).file_name()
+}
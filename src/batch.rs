@@ -0,0 +1,52 @@
+use futures_util::future::join_all;
+use std::{collections::HashMap, future::Future, sync::Arc};
+use tokio::sync::Semaphore;
+
+// runs client calls with a global concurrency limit, and optionally a tighter one per
+// category (e.g. "offers", "points"), so mixed workflows don't each need their own limiting
+#[derive(Debug, Clone)]
+pub struct BatchExecutor {
+    global: Arc<Semaphore>,
+    categories: HashMap<String, Arc<Semaphore>>,
+}
+
+impl BatchExecutor {
+    pub fn new(concurrency: usize) -> Self {
+        Self {
+            global: Arc::new(Semaphore::new(concurrency.max(1))),
+            categories: HashMap::new(),
+        }
+    }
+
+    pub fn with_category_limit(mut self, category: &str, concurrency: usize) -> Self {
+        self.categories.insert(
+            category.to_string(),
+            Arc::new(Semaphore::new(concurrency.max(1))),
+        );
+        self
+    }
+
+    // categories without a limit are only bound by the global one
+    pub async fn run<F, T>(&self, category: &str, task: F) -> T
+    where
+        F: Future<Output = T>,
+    {
+        // the semaphores are never closed, so acquiring can't fail
+        let _category_permit = match self.categories.get(category) {
+            Some(semaphore) => Some(semaphore.acquire().await.unwrap()),
+            None => None,
+        };
+        let _global_permit = self.global.acquire().await.unwrap();
+
+        task.await
+    }
+
+    // results are returned in the same order as `tasks`
+    pub async fn run_all<I, F, T>(&self, category: &str, tasks: I) -> Vec<T>
+    where
+        I: IntoIterator<Item = F>,
+        F: Future<Output = T>,
+    {
+        join_all(tasks.into_iter().map(|task| self.run(category, task))).await
+    }
+}
@@ -0,0 +1,121 @@
+use crate::types::request::Device;
+use serde_derive::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientConfig {
+    pub base_url: String,
+    pub client_id: String,
+    pub market: Option<String>,
+    pub accept_language: Option<String>,
+    pub device: Option<Device>,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    pub login_token: Option<String>,
+    pub auth_token: Option<String>,
+    pub refresh_token: Option<String>,
+    pub store: Option<StoreContext>,
+    pub source_app: Option<SourceApp>,
+}
+
+// the store used by the *_default_store methods, `offset` is the store's timezone offset in minutes
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoreContext {
+    pub store_id: String,
+    pub offset: i64,
+}
+
+impl Debug for ClientConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClientConfig")
+            .field("base_url", &self.base_url)
+            .field("client_id", &self.client_id)
+            .field("market", &self.market)
+            .field("accept_language", &self.accept_language)
+            .field("device", &self.device)
+            .field("headers", &self.headers)
+            .field(
+                "login_token",
+                &self.login_token.as_ref().map(|_| "[redacted]"),
+            )
+            .field(
+                "auth_token",
+                &self.auth_token.as_ref().map(|_| "[redacted]"),
+            )
+            .field(
+                "refresh_token",
+                &self.refresh_token.as_ref().map(|_| "[redacted]"),
+            )
+            .field("store", &self.store)
+            .field("source_app", &self.source_app)
+            .finish()
+    }
+}
+
+pub const PRODUCTION_BASE_URL: &str = "https://ap-prod.api.mcd.com";
+pub const PRODUCTION_IMAGE_BASE_URL: &str =
+    "https://au-prod-us-cds-oceofferimages.s3.amazonaws.com/oce3-au-prod/offers";
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Environment {
+    #[default]
+    Production,
+    // api and offer images are both served from this url, images under /images
+    Mock(String),
+}
+
+impl Environment {
+    pub fn base_url(&self) -> String {
+        match self {
+            Environment::Production => PRODUCTION_BASE_URL.to_string(),
+            Environment::Mock(url) => url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub fn image_base_url(&self) -> String {
+        match self {
+            Environment::Production => PRODUCTION_IMAGE_BASE_URL.to_string(),
+            Environment::Mock(url) => format!("{}/images", url.trim_end_matches('/')),
+        }
+    }
+}
+
+// which client the requests claim to come from, some endpoints behave differently for web ordering
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SourceApp {
+    #[default]
+    Gma,
+    Web,
+}
+
+impl SourceApp {
+    pub fn header_value(&self) -> &'static str {
+        match self {
+            SourceApp::Gma => "GMA",
+            SourceApp::Web => "WEB",
+        }
+    }
+
+    pub fn user_agent(&self) -> &'static str {
+        match self {
+            SourceApp::Gma => "MCDSDK/29.0.8 (Android; 33; en-AU) GMA/8.6.2",
+            SourceApp::Web => "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
+        }
+    }
+
+    // the app user agent reports the android api level, so it follows the device when one is set
+    pub fn user_agent_for(&self, device: Option<&Device>) -> Cow<'static, str> {
+        match (self, device.and_then(|d| d.android_api_level())) {
+            (SourceApp::Gma, Some(api_level)) => Cow::Owned(format!(
+                "MCDSDK/29.0.8 (Android; {api_level}; en-AU) GMA/8.6.2"
+            )),
+            _ => Cow::Borrowed(self.user_agent()),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::fmt::Display;
+
+// account emails of the form mailbox+tag-sequence@domain, everything after the + is
+// delivered to mailbox@domain by providers that support plus addressing
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PlusAddress {
+    pub mailbox: String,
+    pub domain: String,
+    pub tag: String,
+    pub sequence: Option<u64>,
+}
+
+impl PlusAddress {
+    // `base` is the real mailbox, e.g. accounts@example.com
+    pub fn new(base: &str, tag: &str) -> Option<Self> {
+        let (mailbox, domain) = split_email(base)?;
+        // a tag ending in -<number> would be read back as a sequence by `parse`
+        if mailbox.contains('+') || !is_valid_tag(tag) || has_sequence_suffix(tag) {
+            return None;
+        }
+
+        Some(Self {
+            mailbox: mailbox.to_string(),
+            domain: domain.to_string(),
+            tag: tag.to_string(),
+            sequence: None,
+        })
+    }
+
+    pub fn with_sequence(mut self, sequence: u64) -> Self {
+        self.sequence = Some(sequence);
+        self
+    }
+
+    // a trailing -<number> on the tag is read as the sequence
+    pub fn parse(email: &str) -> Option<Self> {
+        let (local, domain) = split_email(email)?;
+        let (mailbox, tag) = local.split_once('+')?;
+        if mailbox.is_empty() {
+            return None;
+        }
+
+        let (tag, sequence) = match tag.rsplit_once('-') {
+            Some((head, sequence)) => match sequence.parse::<u64>() {
+                Ok(sequence) => (head, Some(sequence)),
+                Err(_) => (tag, None),
+            },
+            None => (tag, None),
+        };
+
+        if !is_valid_tag(tag) {
+            return None;
+        }
+
+        Some(Self {
+            mailbox: mailbox.to_string(),
+            domain: domain.to_string(),
+            tag: tag.to_string(),
+            sequence,
+        })
+    }
+
+    // the address mail is actually delivered to
+    pub fn base(&self) -> String {
+        format!("{}@{}", self.mailbox, self.domain)
+    }
+}
+
+impl Display for PlusAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}+{}", self.mailbox, self.tag)?;
+        if let Some(sequence) = self.sequence {
+            write!(f, "-{sequence}")?;
+        }
+
+        write!(f, "@{}", self.domain)
+    }
+}
+
+fn split_email(email: &str) -> Option<(&str, &str)> {
+    let (local, domain) = email.trim().rsplit_once('@')?;
+    if local.is_empty() || !domain.contains('.') || domain.starts_with('.') {
+        return None;
+    }
+
+    Some((local, domain))
+}
+
+fn has_sequence_suffix(tag: &str) -> bool {
+    tag.rsplit_once('-')
+        .is_some_and(|(_, suffix)| suffix.parse::<u64>().is_ok())
+}
+
+fn is_valid_tag(tag: &str) -> bool {
+    !tag.is_empty()
+        && tag
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PlusAddress;
+
+    #[test]
+    fn round_trips_through_display_and_parse() {
+        let address = PlusAddress::new("accounts@example.com", "maccas-au")
+            .unwrap()
+            .with_sequence(5);
+
+        assert_eq!(address.to_string(), "accounts+maccas-au-5@example.com");
+        assert_eq!(PlusAddress::parse(&address.to_string()), Some(address));
+    }
+
+    #[test]
+    fn rejects_tags_that_look_like_a_sequence() {
+        assert_eq!(PlusAddress::new("a@b.com", "tag-5"), None);
+    }
+}
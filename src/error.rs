@@ -1,11 +1,46 @@
 use std::{error::Error, fmt::Display, num::ParseIntError};
 
+use chrono::{DateTime, Utc};
 use http::StatusCode;
+use serde_json::Value;
+
+use crate::types::response::Status;
 
 #[derive(Debug)]
 pub enum ClientError {
     RequestOrMiddlewareError(reqwest_middleware::Error),
     RequestError(reqwest::Error),
+    DealStackLimitReached {
+        limit: usize,
+    },
+    UnexpectedContentType {
+        status: StatusCode,
+        content_type: String,
+        body: String,
+    },
+    Maintenance {
+        until: Option<DateTime<Utc>>,
+    },
+    Registration {
+        status: StatusCode,
+        error: RegistrationError,
+    },
+    SchemaMismatch {
+        expected: u32,
+        found: u32,
+    },
+    TableNumberOutOfRange {
+        table_number: i64,
+        min: i64,
+        max: i64,
+    },
+    InvalidClientCredentials {
+        status: StatusCode,
+        message: Option<String>,
+    },
+    ResponseTooLarge {
+        limit: usize,
+    },
     Other(anyhow::Error),
 }
 
@@ -27,6 +62,12 @@ impl From<reqwest::Error> for ClientError {
     }
 }
 
+impl From<serde_json::Error> for ClientError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Other(e.into())
+    }
+}
+
 impl From<ParseIntError> for ClientError {
     fn from(e: ParseIntError) -> Self {
         Self::Other(e.into())
@@ -41,6 +82,14 @@ impl ClientError {
                 reqwest_middleware::Error::Reqwest(e) => e.status(),
             },
             ClientError::RequestError(e) => e.status(),
+            ClientError::DealStackLimitReached { .. } => None,
+            ClientError::UnexpectedContentType { status, .. } => Some(*status),
+            ClientError::Maintenance { .. } => Some(StatusCode::SERVICE_UNAVAILABLE),
+            ClientError::Registration { status, .. } => Some(*status),
+            ClientError::SchemaMismatch { .. } => None,
+            ClientError::TableNumberOutOfRange { .. } => None,
+            ClientError::InvalidClientCredentials { status, .. } => Some(*status),
+            ClientError::ResponseTooLarge { .. } => None,
             ClientError::Other(_) => None,
         }
     }
@@ -51,6 +100,14 @@ impl Error for ClientError {
         match self {
             ClientError::RequestOrMiddlewareError(e) => Some(e),
             ClientError::RequestError(e) => Some(e),
+            ClientError::DealStackLimitReached { .. } => None,
+            ClientError::UnexpectedContentType { .. } => None,
+            ClientError::Maintenance { .. } => None,
+            ClientError::Registration { error, .. } => Some(error),
+            ClientError::SchemaMismatch { .. } => None,
+            ClientError::TableNumberOutOfRange { .. } => None,
+            ClientError::InvalidClientCredentials { .. } => None,
+            ClientError::ResponseTooLarge { .. } => None,
             ClientError::Other(e) => e.source(),
         }
     }
@@ -69,7 +126,92 @@ impl Display for ClientError {
         match self {
             ClientError::RequestOrMiddlewareError(e) => e.fmt(f),
             ClientError::RequestError(e) => e.fmt(f),
+            ClientError::DealStackLimitReached { limit } => {
+                write!(f, "deal stack limit of {limit} offers reached")
+            }
+            ClientError::UnexpectedContentType {
+                status,
+                content_type,
+                body,
+            } => write!(
+                f,
+                "unexpected {content_type} response with status {status}: {body}"
+            ),
+            ClientError::Maintenance { until } => match until {
+                Some(until) => write!(f, "api is under maintenance until {until}"),
+                None => write!(f, "api is under maintenance"),
+            },
+            ClientError::Registration { error, .. } => error.fmt(f),
+            ClientError::SchemaMismatch { expected, found } => write!(
+                f,
+                "cached data has schema version {found}, expected {expected}"
+            ),
+            ClientError::TableNumberOutOfRange {
+                table_number,
+                min,
+                max,
+            } => write!(
+                f,
+                "table number {table_number} is outside the store's range of {min} to {max}"
+            ),
+            ClientError::InvalidClientCredentials { status, message } => match message {
+                Some(message) => write!(f, "invalid client credentials ({status}): {message}"),
+                None => write!(f, "invalid client credentials ({status})"),
+            },
+            ClientError::ResponseTooLarge { limit } => {
+                write!(f, "response body is larger than the {limit} byte limit")
+            }
             ClientError::Other(e) => e.fmt(f),
         }
     }
 }
+
+// the api's codes for weak passwords, bad zip codes and the like aren't documented, so only a
+// conflict is given a meaning and everything else keeps the code and message for callers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegistrationError {
+    DuplicateEmail {
+        message: Option<String>,
+    },
+    Unknown {
+        code: Value,
+        message: Option<String>,
+    },
+}
+
+impl RegistrationError {
+    pub(crate) fn from_status(http_status: StatusCode, status: Status) -> Self {
+        match http_status {
+            StatusCode::CONFLICT => Self::DuplicateEmail {
+                message: status.message,
+            },
+            _ => Self::Unknown {
+                code: status.code,
+                message: status.message,
+            },
+        }
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            RegistrationError::DuplicateEmail { message } => message.as_deref(),
+            RegistrationError::Unknown { message, .. } => message.as_deref(),
+        }
+    }
+}
+
+impl Error for RegistrationError {}
+
+impl Display for RegistrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            RegistrationError::DuplicateEmail { .. } => "email is already registered".to_string(),
+            RegistrationError::Unknown { code, .. } => format!("registration failed ({code})"),
+        };
+
+        match self.message() {
+            Some(message) => write!(f, "{reason}: {message}"),
+            None => write!(f, "{reason}"),
+        }
+    }
+}
@@ -0,0 +1,18 @@
+use http::StatusCode;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientEvent {
+    TokenRefreshed,
+    AccountBlocked {
+        status: StatusCode,
+        resource: String,
+    },
+    OfferRedeemed {
+        offer_id: String,
+        store_id: String,
+    },
+    RewardRedeemed {
+        reward_id: String,
+        store_id: String,
+    },
+}
@@ -1,9 +1,31 @@
 mod api;
+mod batch;
+mod clock;
+mod config;
+mod email;
 mod error;
+mod event;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod pipeline;
+mod rate_limit;
+mod registry;
+mod signer;
 pub mod types;
 
 pub type ClientResult<T> = Result<T, ClientError>;
 
 pub use api::ApiClient;
-pub use error::ClientError;
+pub use batch::BatchExecutor;
+pub use clock::{Clock, FixedClock, SystemClock};
+pub use config::{ClientConfig, Environment, SourceApp, StoreContext};
+pub use email::PlusAddress;
+pub use error::{ClientError, RegistrationError};
+pub use event::ClientEvent;
+#[cfg(feature = "metrics")]
+pub use metrics::Metrics;
+pub use pipeline::OfferPipeline;
+pub use rate_limit::RateLimiter;
+pub use registry::MarketRegistry;
+pub use signer::RequestSigner;
 pub use types::response::ClientResponse;
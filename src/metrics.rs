@@ -0,0 +1,89 @@
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// counters shared between clients, `render` gives the prometheus text format so any http
+// framework can serve it from a /metrics route
+#[derive(Default, Debug)]
+pub struct Metrics {
+    requests: AtomicU64,
+    responses_2xx: AtomicU64,
+    responses_3xx: AtomicU64,
+    responses_4xx: AtomicU64,
+    responses_5xx: AtomicU64,
+    responses_other: AtomicU64,
+    responses_transport: AtomicU64,
+    token_refreshes: AtomicU64,
+    accounts_blocked: AtomicU64,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn record_request(&self) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    // `status` is None when the request failed before a response came back
+    pub(crate) fn record_response(&self, status: Option<u16>) {
+        let counter = match status {
+            Some(200..=299) => &self.responses_2xx,
+            Some(300..=399) => &self.responses_3xx,
+            Some(400..=499) => &self.responses_4xx,
+            Some(500..=599) => &self.responses_5xx,
+            Some(_) => &self.responses_other,
+            None => &self.responses_transport,
+        };
+
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_token_refresh(&self) {
+        self.token_refreshes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_account_blocked(&self) {
+        self.accounts_blocked.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        let _ = writeln!(out, "# TYPE maccas_requests_total counter");
+        let _ = writeln!(
+            out,
+            "maccas_requests_total {}",
+            self.requests.load(Ordering::Relaxed)
+        );
+
+        let _ = writeln!(out, "# TYPE maccas_responses_total counter");
+        for (class, counter) in [
+            ("2xx", &self.responses_2xx),
+            ("3xx", &self.responses_3xx),
+            ("4xx", &self.responses_4xx),
+            ("5xx", &self.responses_5xx),
+            ("other", &self.responses_other),
+            ("transport", &self.responses_transport),
+        ] {
+            let count = counter.load(Ordering::Relaxed);
+            let _ = writeln!(out, "maccas_responses_total{{class=\"{class}\"}} {count}");
+        }
+
+        let _ = writeln!(out, "# TYPE maccas_token_refreshes_total counter");
+        let _ = writeln!(
+            out,
+            "maccas_token_refreshes_total {}",
+            self.token_refreshes.load(Ordering::Relaxed)
+        );
+
+        let _ = writeln!(out, "# TYPE maccas_accounts_blocked_total counter");
+        let _ = writeln!(
+            out,
+            "maccas_accounts_blocked_total {}",
+            self.accounts_blocked.load(Ordering::Relaxed)
+        );
+
+        out
+    }
+}
@@ -0,0 +1,49 @@
+use crate::types::response::Offer;
+use std::collections::HashSet;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OfferPipeline {
+    pub dedupe: bool,
+    pub remove_archived: bool,
+    pub remove_redeemed: bool,
+    pub normalize_names: bool,
+}
+
+impl OfferPipeline {
+    pub fn all() -> Self {
+        Self {
+            dedupe: true,
+            remove_archived: true,
+            remove_redeemed: true,
+            normalize_names: true,
+        }
+    }
+
+    pub fn apply(&self, offers: &mut Vec<Offer>) {
+        if self.remove_archived {
+            offers.retain(|o| !o.is_archived);
+        }
+
+        if self.remove_redeemed {
+            offers.retain(|o| !o.is_redeemed);
+        }
+
+        // the same proposition is returned once per offer instance, keep the first
+        if self.dedupe {
+            let mut seen = HashSet::new();
+            offers.retain(|o| seen.insert(o.offer_proposition_id));
+        }
+
+        if self.normalize_names {
+            for offer in offers.iter_mut() {
+                offer.name = normalize(&offer.name);
+                offer.short_description = normalize(&offer.short_description);
+            }
+        }
+    }
+}
+
+// names come back with embedded newlines and doubled spaces
+fn normalize(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
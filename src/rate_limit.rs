@@ -0,0 +1,11 @@
+use crate::ClientResult;
+use async_trait::async_trait;
+use reqwest::Method;
+
+// consulted before every request is sent, implementations can wait or return an error to
+// stop the request. no shared implementation ships with the crate, callers that run many
+// processes behind one egress ip can back one with redis or similar to coordinate their pace
+#[async_trait]
+pub trait RateLimiter: Send + Sync {
+    async fn acquire(&self, method: &Method, path: &str) -> ClientResult<()>;
+}
@@ -0,0 +1,76 @@
+#[cfg(feature = "metrics")]
+use crate::Metrics;
+use crate::{ApiClient, ClientConfig, ClientResult};
+use reqwest_middleware::ClientWithMiddleware;
+use std::collections::HashMap;
+#[cfg(feature = "metrics")]
+use std::sync::Arc;
+
+// one client per market, keyed by the upper cased market code. clients built with
+// `from_configs` share the same middleware stack and connection pool, and every client in the
+// registry reports to the registry's metrics
+#[derive(Default, Clone)]
+pub struct MarketRegistry {
+    clients: HashMap<String, ApiClient>,
+    #[cfg(feature = "metrics")]
+    metrics: Arc<Metrics>,
+}
+
+impl MarketRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_configs(
+        configs: &[ClientConfig],
+        client: &ClientWithMiddleware,
+    ) -> ClientResult<Self> {
+        let mut registry = Self::new();
+        for config in configs {
+            registry.insert(ApiClient::from_config(config, client.clone())?);
+        }
+
+        Ok(registry)
+    }
+
+    // replaces any client already registered for the same market
+    pub fn insert(&mut self, client: ApiClient) -> Option<ApiClient> {
+        #[cfg(feature = "metrics")]
+        let client = self.with_shared_metrics(client);
+
+        self.clients
+            .insert(client.market().to_ascii_uppercase(), client)
+    }
+
+    // shared by every client in the registry, render it once for all markets
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> &Arc<Metrics> {
+        &self.metrics
+    }
+
+    #[cfg(feature = "metrics")]
+    fn with_shared_metrics(&self, mut client: ApiClient) -> ApiClient {
+        client.set_metrics(self.metrics.clone());
+        client
+    }
+
+    pub fn get(&self, market: &str) -> Option<&ApiClient> {
+        self.clients.get(&market.to_ascii_uppercase())
+    }
+
+    pub fn get_mut(&mut self, market: &str) -> Option<&mut ApiClient> {
+        self.clients.get_mut(&market.to_ascii_uppercase())
+    }
+
+    pub fn remove(&mut self, market: &str) -> Option<ApiClient> {
+        self.clients.remove(&market.to_ascii_uppercase())
+    }
+
+    pub fn markets(&self) -> impl Iterator<Item = &str> {
+        self.clients.keys().map(|m| m.as_str())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ApiClient)> {
+        self.clients.iter().map(|(m, c)| (m.as_str(), c))
+    }
+}
@@ -0,0 +1,8 @@
+use crate::ClientResult;
+use http::HeaderMap;
+use reqwest::Method;
+
+pub trait RequestSigner: Send + Sync {
+    // returned headers are added to the request, replacing any with the same name
+    fn sign(&self, method: &Method, path: &str, body: Option<&[u8]>) -> ClientResult<HeaderMap>;
+}
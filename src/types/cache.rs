@@ -0,0 +1,59 @@
+use crate::{ClientError, ClientResult};
+use serde::de::DeserializeOwned;
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+// bump whenever a change to the types would deserialize old cached data differently
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Versioned<T> {
+    pub schema_version: u32,
+    pub data: T,
+}
+
+impl<T> Versioned<T> {
+    pub fn new(data: T) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            data,
+        }
+    }
+}
+
+impl<T> Versioned<T>
+where
+    T: serde::Serialize,
+{
+    pub fn to_json(&self) -> ClientResult<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+// refuses data written with a different schema version
+pub fn from_cached<T>(json: &str) -> ClientResult<T>
+where
+    T: DeserializeOwned,
+{
+    from_cached_with(json, |found, _| {
+        Err(ClientError::SchemaMismatch {
+            expected: SCHEMA_VERSION,
+            found,
+        })
+    })
+}
+
+// hands data written with a different schema version to `migrate` as raw json
+pub fn from_cached_with<T, F>(json: &str, migrate: F) -> ClientResult<T>
+where
+    T: DeserializeOwned,
+    F: FnOnce(u32, Value) -> ClientResult<T>,
+{
+    let cached = serde_json::from_str::<Versioned<Value>>(json)?;
+    if cached.schema_version == SCHEMA_VERSION {
+        Ok(serde_json::from_value(cached.data)?)
+    } else {
+        migrate(cached.schema_version, cached.data)
+    }
+}
@@ -1,2 +1,4 @@
+pub mod cache;
+pub mod money;
 pub mod request;
 pub mod response;
@@ -0,0 +1,134 @@
+use serde_derive::{Deserialize, Serialize};
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Currency {
+    Aud,
+    Nzd,
+    Gbp,
+    Usd,
+    Cad,
+    Eur,
+}
+
+impl Currency {
+    // `market` is the same code passed to set_market, e.g. "AU"
+    pub fn for_market(market: &str) -> Option<Self> {
+        match market.trim().to_ascii_uppercase().as_str() {
+            "AU" => Some(Self::Aud),
+            "NZ" => Some(Self::Nzd),
+            "UK" | "GB" => Some(Self::Gbp),
+            "US" => Some(Self::Usd),
+            "CA" => Some(Self::Cad),
+            "DE" | "FR" | "IE" | "NL" | "AT" | "ES" | "IT" => Some(Self::Eur),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::Aud => "AUD",
+            Currency::Nzd => "NZD",
+            Currency::Gbp => "GBP",
+            Currency::Usd => "USD",
+            Currency::Cad => "CAD",
+            Currency::Eur => "EUR",
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            Currency::Aud | Currency::Nzd | Currency::Usd | Currency::Cad => "$",
+            Currency::Gbp => "£",
+            Currency::Eur => "€",
+        }
+    }
+}
+
+// amounts are kept in cents so sums don't pick up floating point error, the api itself
+// reports prices as dollars
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Money {
+    pub cents: i64,
+    pub currency: Currency,
+}
+
+impl Money {
+    pub fn from_cents(cents: i64, currency: Currency) -> Self {
+        Self { cents, currency }
+    }
+
+    pub fn from_dollars(dollars: f64, currency: Currency) -> Self {
+        Self {
+            cents: (dollars * 100.0).round() as i64,
+            currency,
+        }
+    }
+
+    pub fn dollars(&self) -> f64 {
+        self.cents as f64 / 100.0
+    }
+
+    // None when the currencies differ or the sum overflows
+    pub fn checked_add(&self, other: Money) -> Option<Money> {
+        if self.currency != other.currency {
+            return None;
+        }
+
+        self.cents
+            .checked_add(other.cents)
+            .map(|cents| Money::from_cents(cents, self.currency))
+    }
+
+    // separators and symbol placement follow the market rather than the currency, e.g. the
+    // same euro amount is €1.50 in IE, € 1,50 in NL and 1,50 € in DE
+    pub fn format_for(&self, market: &str) -> String {
+        let sign = if self.cents < 0 { "-" } else { "" };
+        let whole = self.cents.unsigned_abs() / 100;
+        let fraction = self.cents.unsigned_abs() % 100;
+        let symbol = self.currency.symbol();
+
+        match market.trim().to_ascii_uppercase().as_str() {
+            "NL" => format!("{sign}{symbol} {whole},{fraction:02}"),
+            "DE" | "FR" | "AT" | "ES" | "IT" => format!("{sign}{whole},{fraction:02} {symbol}"),
+            _ => format!("{sign}{symbol}{whole}.{fraction:02}"),
+        }
+    }
+}
+
+impl Display for Money {
+    // without a market euro amounts use the german format
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let market = match self.currency {
+            Currency::Eur => "DE",
+            _ => "",
+        };
+
+        f.write_str(&self.format_for(market))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Currency, Money};
+
+    #[test]
+    fn formats_per_market() {
+        let aud = Money::from_cents(1250, Currency::Aud);
+        let eur = Money::from_cents(150, Currency::Eur);
+
+        assert_eq!(aud.format_for("AU"), "$12.50");
+        assert_eq!(eur.format_for("IE"), "€1.50");
+        assert_eq!(eur.format_for("DE"), "1,50 €");
+        assert_eq!(eur.format_for("NL"), "€ 1,50");
+    }
+
+    #[test]
+    fn formats_negative_amounts() {
+        let aud = Money::from_cents(-5, Currency::Aud);
+
+        assert_eq!(aud.format_for("AU"), "-$0.05");
+    }
+}
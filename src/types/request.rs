@@ -1,5 +1,6 @@
 use serde_derive::Deserialize;
 use serde_derive::Serialize;
+use uuid::Uuid;
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -82,6 +83,81 @@ pub struct Device {
     pub timezone: String,
 }
 
+// the android api levels a device can be built for, with the os version each one reports
+const ANDROID_VERSIONS: [(u32, &str); 6] = [
+    (34, "14"),
+    (33, "13"),
+    (31, "12"),
+    (30, "11"),
+    (29, "10"),
+    (28, "9"),
+];
+
+impl Device {
+    // None for api levels without a known os version
+    pub fn android(api_level: u32, device_id: &Uuid, timezone: &str) -> Option<Self> {
+        let (_, os_version) = ANDROID_VERSIONS
+            .iter()
+            .find(|(level, _)| *level == api_level)?;
+
+        Some(Self {
+            device_id: device_id.simple().to_string()[..16].to_string(),
+            device_id_type: String::from("AndroidId"),
+            is_active: String::from("Y"),
+            os: String::from("android"),
+            os_version: os_version.to_string(),
+            timezone: timezone.to_string(),
+        })
+    }
+
+    pub fn ios(version: &str, device_id: &Uuid, timezone: &str) -> Self {
+        Self {
+            device_id: device_id.as_hyphenated().to_string().to_uppercase(),
+            device_id_type: String::from("IDFV"),
+            is_active: String::from("Y"),
+            os: String::from("ios"),
+            os_version: version.to_string(),
+            timezone: timezone.to_string(),
+        }
+    }
+
+    pub fn with_timezone(mut self, timezone: &str) -> Self {
+        self.timezone = timezone.to_string();
+        self
+    }
+
+    // None for ios devices
+    pub fn android_api_level(&self) -> Option<u32> {
+        if !self.os.eq_ignore_ascii_case("android") {
+            return None;
+        }
+
+        ANDROID_VERSIONS
+            .iter()
+            .find(|(_, os_version)| *os_version == self.os_version)
+            .map(|(level, _)| *level)
+    }
+
+    // `market` is the same code passed to set_market, e.g. "AU"
+    pub fn timezone_for_market(market: &str) -> Option<&'static str> {
+        match market.trim().to_ascii_uppercase().as_str() {
+            "AU" => Some("Australia/Sydney"),
+            "NZ" => Some("Pacific/Auckland"),
+            "UK" | "GB" => Some("Europe/London"),
+            "US" => Some("America/New_York"),
+            "CA" => Some("America/Toronto"),
+            "DE" => Some("Europe/Berlin"),
+            "FR" => Some("Europe/Paris"),
+            "IE" => Some("Europe/Dublin"),
+            "NL" => Some("Europe/Amsterdam"),
+            "AT" => Some("Europe/Vienna"),
+            "ES" => Some("Europe/Madrid"),
+            "IT" => Some("Europe/Rome"),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Policies {
@@ -129,3 +205,329 @@ pub struct ActivationRequest {
     pub credentials: Credentials,
     pub device_id: String,
 }
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryRequest {
+    pub customer_identifier: String,
+    pub device_id: String,
+    pub registration_type: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryVerificationRequest {
+    pub customer_identifier: String,
+    pub verification_code: String,
+    pub device_id: String,
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordResetRequest {
+    pub recovery_token: String,
+    pub new_password: String,
+    pub device_id: String,
+}
+
+impl std::fmt::Debug for PasswordResetRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PasswordResetRequest")
+            .field("recovery_token", &"[redacted]")
+            .field("new_password", &"[redacted]")
+            .field("device_id", &self.device_id)
+            .finish()
+    }
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePasswordRequest {
+    pub old_password: String,
+    pub new_password: String,
+}
+
+impl std::fmt::Debug for ChangePasswordRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChangePasswordRequest")
+            .field("old_password", &"[redacted]")
+            .field("new_password", &"[redacted]")
+            .finish()
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceRegistrationRequest {
+    pub device: Device,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushTokenRequest {
+    pub device_id: String,
+    pub push_token: String,
+    // FCM or APNS
+    pub push_provider: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPreferencesRequest {
+    pub preferences: Vec<NotificationPreference>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPreference {
+    pub preference_id: i64,
+    pub details: NotificationChannels,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationChannels {
+    #[serde(rename = "Email", skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(rename = "MobileApp", skip_serializing_if = "Option::is_none")]
+    pub mobile_app: Option<String>,
+    #[serde(rename = "SMS", skip_serializing_if = "Option::is_none")]
+    pub sms: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionsRequest {
+    pub subscriptions: Vec<Subscription>,
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentMethodRequest {
+    // e.g. CARD or PAYPAL
+    pub payment_type: String,
+    // tokenized card or vault reference from the payment provider
+    pub payment_token: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nick_name: Option<String>,
+    pub is_preferred: bool,
+}
+
+impl std::fmt::Debug for PaymentMethodRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PaymentMethodRequest")
+            .field("payment_type", &self.payment_type)
+            .field("payment_token", &"[redacted]")
+            .field("nick_name", &self.nick_name)
+            .field("is_preferred", &self.is_preferred)
+            .finish()
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestaurantLocationQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latitude: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub longitude: Option<f64>,
+    pub filter: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store_unique_id_type: Option<String>,
+    // comma separated, e.g. "DRIVETHRU,WIFI"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub facilities: Option<String>,
+}
+
+impl RestaurantLocationQuery {
+    pub fn near(latitude: f64, longitude: f64, distance: f64) -> Self {
+        Self {
+            distance: Some(distance),
+            latitude: Some(latitude),
+            longitude: Some(longitude),
+            filter: "summary".to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_filter(mut self, filter: &str) -> Self {
+        self.filter = filter.to_string();
+        self
+    }
+
+    pub fn with_store_unique_id_type(mut self, store_unique_id_type: &str) -> Self {
+        self.store_unique_id_type = Some(store_unique_id_type.to_string());
+        self
+    }
+
+    pub fn with_facilities(mut self, facilities: &[&str]) -> Self {
+        self.facilities = Some(facilities.join(","));
+        self
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CartRequest {
+    pub store_id: String,
+    // minutes from utc, same as the offset used by the dealstack
+    pub offset: i64,
+    pub items: Vec<CartItem>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub offers: Vec<CartOffer>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CartItem {
+    pub product_code: String,
+    pub quantity: i64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub choices: Vec<CartItem>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CartOffer {
+    pub offer_id: i64,
+    pub offer_proposition_id: String,
+}
+
+impl CartRequest {
+    pub fn new(store_id: &str, offset: i64) -> Self {
+        Self {
+            store_id: store_id.to_string(),
+            offset,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_item(mut self, product_code: &str, quantity: i64) -> Self {
+        self.items.push(CartItem {
+            product_code: product_code.to_string(),
+            quantity,
+            choices: Vec::new(),
+        });
+        self
+    }
+
+    pub fn with_offer(mut self, offer_id: i64, offer_proposition_id: &str) -> Self {
+        self.offers.push(CartOffer {
+            offer_id,
+            offer_proposition_id: offer_proposition_id.to_string(),
+        });
+        self
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderRequest {
+    #[serde(flatten)]
+    pub cart: CartRequest,
+    pub pickup_type: PickupType,
+    pub payment: OrderPayment,
+    // `random_code` of the deal stack to apply, the offers have to already be in the stack
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deal_stack_code: Option<String>,
+    // store local time, e.g. 2022-06-01T12:30:00. orders without one are prepared straight away
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheduled_pickup_time: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PickupType {
+    #[default]
+    FrontCounter,
+    DriveThru,
+    TableService,
+    Curbside,
+    Delivery,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderPayment {
+    // id of a payment method added with add_payment_method
+    pub payment_method_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_reference: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckInRequest {
+    pub pickup_type: PickupType,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableServiceCheckInRequest {
+    pub pickup_type: PickupType,
+    pub table_number: i64,
+}
+
+impl TableServiceCheckInRequest {
+    pub fn new(table_number: i64) -> Self {
+        Self {
+            pickup_type: PickupType::TableService,
+            table_number,
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurbsideCheckInRequest {
+    pub pickup_type: PickupType,
+    pub parking_bay_number: String,
+}
+
+impl CurbsideCheckInRequest {
+    pub fn new(parking_bay_number: &str) -> Self {
+        Self {
+            pickup_type: PickupType::Curbside,
+            parking_bay_number: parking_bay_number.to_string(),
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryEstimateRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<DeliveryAddress>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latitude: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub longitude: Option<f64>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryAddress {
+    pub address_line1: String,
+    pub city_town: String,
+    pub postal_zip: String,
+    pub country: String,
+}
+
+impl DeliveryEstimateRequest {
+    pub fn at(latitude: f64, longitude: f64) -> Self {
+        Self {
+            latitude: Some(latitude),
+            longitude: Some(longitude),
+            ..Default::default()
+        }
+    }
+
+    pub fn for_address(address: DeliveryAddress) -> Self {
+        Self {
+            address: Some(address),
+            ..Default::default()
+        }
+    }
+}
@@ -1,41 +1,320 @@
+use crate::types::money::{Currency, Money};
+use crate::types::request::NotificationPreference;
 use crate::ClientError;
+use bytes::Bytes;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc, Weekday};
+use http::header::{CONTENT_LANGUAGE, CONTENT_TYPE, RETRY_AFTER, WARNING};
 use http::HeaderMap;
 use http::StatusCode;
 use serde_derive::Deserialize;
 use serde_derive::Serialize;
 use serde_json::Value;
+use std::collections::{BTreeSet, HashSet};
 use std::fmt::Debug;
+use std::ops::RangeInclusive;
 
 pub struct ClientResponse<T> {
     pub status: StatusCode,
     pub headers: HeaderMap,
+    pub language: Option<String>,
+    pub warnings: Vec<String>,
+    pub raw_body: Option<Bytes>,
+    // elements dropped by lenient parsing, always empty otherwise
+    pub skipped: Vec<SkippedElement>,
     pub body: T,
 }
 
+#[derive(Debug)]
+pub struct SkippedElement {
+    pub index: usize,
+    pub error: serde_json::Error,
+}
+
+// parses each element on its own, so one bad element doesn't fail the rest
+pub fn from_values_lenient<T>(values: Vec<Value>) -> (Vec<T>, Vec<SkippedElement>)
+where
+    T: for<'de> serde::Deserialize<'de>,
+{
+    let mut parsed = Vec::with_capacity(values.len());
+    let mut skipped = Vec::new();
+    for (index, value) in values.into_iter().enumerate() {
+        match serde_json::from_value::<T>(value) {
+            Ok(element) => parsed.push(element),
+            Err(error) => skipped.push(SkippedElement { index, error }),
+        }
+    }
+
+    (parsed, skipped)
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct RequestedLanguage(pub String);
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RetainRawBody(pub usize);
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MaxBodySize(pub usize);
+
+// the client clock's time when the response came back, so relative headers follow it
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ReceivedAt(pub DateTime<Utc>);
+
 impl<T: Debug> Debug for ClientResponse<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("ClientResponse")
             .field("status", &self.status)
+            .field("language", &self.language)
+            .field("warnings", &self.warnings)
+            .field("skipped", &self.skipped)
             .field("body", &self.body)
             .finish()
     }
 }
 
-impl<'a, T> ClientResponse<T>
+impl<T> ClientResponse<T>
 where
     T: for<'de> serde::Deserialize<'de> + Debug,
 {
     pub async fn from_response(resp: reqwest::Response) -> Result<Self, ClientError> {
         // return the status error before trying to decode the response to propogate correct error
-        let resp = resp.error_for_status()?;
+        if let Err(e) = resp.error_for_status_ref() {
+            if resp.status() == StatusCode::SERVICE_UNAVAILABLE {
+                let until = maintenance_until(resp.headers(), received_at(&resp));
+                let body = read_text(resp).await?;
+                if until.is_some() || is_maintenance_page(&body) {
+                    return Err(ClientError::Maintenance { until });
+                }
+            }
+
+            return Err(e.into());
+        }
+
+        // the api only speaks json, anything else is usually an error page from somewhere in front of it
+        let content_type = resp
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_ascii_lowercase());
+
+        if let Some(content_type) = content_type {
+            if content_type.starts_with("text/html") || content_type.starts_with("text/plain") {
+                let status = resp.status();
+                let until = maintenance_until(resp.headers(), received_at(&resp));
+                let body = read_text(resp).await?;
+                if is_maintenance_page(&body) {
+                    return Err(ClientError::Maintenance { until });
+                }
+
+                return Err(ClientError::UnexpectedContentType {
+                    status,
+                    content_type,
+                    body,
+                });
+            }
+        }
+
+        // prefer what the api says it returned, otherwise assume it honoured accept-language
+        let language = resp
+            .headers()
+            .get(CONTENT_LANGUAGE)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string())
+            .or_else(|| {
+                resp.extensions()
+                    .get::<RequestedLanguage>()
+                    .map(|l| l.0.clone())
+            });
+
+        let status = resp.status();
+        let headers = resp.headers().clone();
+        let retain_limit = resp.extensions().get::<RetainRawBody>().map(|r| r.0);
+        let bytes = match resp.extensions().get::<MaxBodySize>().map(|m| m.0) {
+            Some(limit) => read_limited(resp, limit).await?,
+            None => resp.bytes().await?,
+        };
+        let body = decode_json::<T>(&bytes).await?;
+
+        // a second pass over just the status, bodies without one simply have no warnings
+        let body_status = serde_json::from_slice::<StatusOnly>(&bytes)
+            .ok()
+            .and_then(|s| s.status);
+
+        let warnings = collect_warnings(&headers, body_status.as_ref());
+        if !warnings.is_empty() {
+            tracing::warn!("response carried warnings: {:?}", warnings);
+        }
+
+        // bodies over the limit are dropped rather than truncated
+        let raw_body = retain_limit
+            .filter(|limit| bytes.len() <= *limit)
+            .map(|_| bytes);
+
         Ok(Self {
-            status: resp.status(),
-            headers: resp.headers().clone(),
-            body: resp.json::<T>().await?,
+            status,
+            headers,
+            language,
+            warnings,
+            raw_body,
+            skipped: Vec::new(),
+            body,
+        })
+    }
+
+    pub fn has_warnings(&self) -> bool {
+        !self.warnings.is_empty()
+    }
+}
+
+impl ClientResponse<Value> {
+    pub(crate) fn parse_body<U>(self) -> Result<ClientResponse<U>, ClientError>
+    where
+        U: for<'de> serde::Deserialize<'de>,
+    {
+        Ok(ClientResponse {
+            status: self.status,
+            headers: self.headers,
+            language: self.language,
+            warnings: self.warnings,
+            raw_body: self.raw_body,
+            skipped: self.skipped,
+            body: serde_json::from_value::<U>(self.body)?,
+        })
+    }
+
+    // the offers are parsed one by one and any that fail are recorded in `skipped`
+    pub(crate) fn into_offers_lenient(self) -> Result<ClientResponse<OfferResponse>, ClientError> {
+        let mut body = self.body;
+        let offers = body
+            .pointer_mut("/response/offers")
+            .map(|offers| offers.take())
+            .and_then(|offers| match offers {
+                Value::Array(offers) => Some(offers),
+                _ => None,
+            })
+            .unwrap_or_default();
+
+        if let Some(slot) = body.pointer_mut("/response/offers") {
+            *slot = Value::Array(Vec::new());
+        }
+
+        let mut parsed = serde_json::from_value::<OfferResponse>(body)?;
+        let (offers, skipped) = from_values_lenient::<Offer>(offers);
+        for element in &skipped {
+            tracing::warn!("skipped offer {}: {}", element.index, element.error);
+        }
+
+        if let Some(list) = parsed.response.as_mut() {
+            list.offers = offers;
+        }
+
+        Ok(ClientResponse {
+            status: self.status,
+            headers: self.headers,
+            language: self.language,
+            warnings: self.warnings,
+            raw_body: self.raw_body,
+            skipped,
+            body: parsed,
         })
     }
 }
 
+// stops reading as soon as the body goes over `limit`, rather than buffering all of it first
+async fn read_limited(mut resp: reqwest::Response, limit: usize) -> Result<Bytes, ClientError> {
+    if resp
+        .content_length()
+        .is_some_and(|length| length > limit as u64)
+    {
+        return Err(ClientError::ResponseTooLarge { limit });
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = resp.chunk().await? {
+        if body.len() + chunk.len() > limit {
+            return Err(ClientError::ResponseTooLarge { limit });
+        }
+
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(Bytes::from(body))
+}
+
+// error bodies are read as text, honouring the client's body size limit when one is set
+pub(crate) async fn read_text(resp: reqwest::Response) -> Result<String, ClientError> {
+    match resp.extensions().get::<MaxBodySize>().map(|m| m.0) {
+        Some(limit) => {
+            let bytes = read_limited(resp, limit).await?;
+            Ok(String::from_utf8_lossy(&bytes).into_owned())
+        }
+        None => Ok(resp.text().await?),
+    }
+}
+
+#[derive(Deserialize)]
+struct StatusOnly {
+    status: Option<Status>,
+}
+
+// decodes through reqwest so failures stay the decode errors `resp.json` used to return
+async fn decode_json<T>(bytes: &Bytes) -> Result<T, ClientError>
+where
+    T: for<'de> serde::Deserialize<'de>,
+{
+    let resp = reqwest::Response::from(http::Response::new(bytes.clone()));
+    Ok(resp.json::<T>().await?)
+}
+
+// successful responses can still carry a message in `status`, e.g. when offer images are degraded
+fn collect_warnings(headers: &HeaderMap, status: Option<&Status>) -> Vec<String> {
+    let mut warnings: Vec<String> = headers
+        .get_all(WARNING)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .map(|v| v.to_string())
+        .collect();
+
+    let type_field = status
+        .and_then(|s| s.type_field.as_deref())
+        .unwrap_or_default();
+    let message = status
+        .and_then(|s| s.message.as_deref())
+        .map(|m| m.trim())
+        .unwrap_or_default();
+
+    if !message.is_empty()
+        && !message.eq_ignore_ascii_case("success")
+        && !message.eq_ignore_ascii_case(type_field)
+    {
+        warnings.push(message.to_string());
+    }
+
+    warnings
+}
+
+fn received_at(resp: &reqwest::Response) -> DateTime<Utc> {
+    resp.extensions()
+        .get::<ReceivedAt>()
+        .map_or_else(Utc::now, |r| r.0)
+}
+
+fn maintenance_until(headers: &HeaderMap, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let retry_after = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    match retry_after.parse::<i64>() {
+        // the header is untrusted, out of range values are treated as missing
+        Ok(seconds) => Duration::try_seconds(seconds).and_then(|d| now.checked_add_signed(d)),
+        Err(_) => DateTime::parse_from_rfc2822(retry_after)
+            .ok()
+            .map(|d| d.with_timezone(&Utc)),
+    }
+}
+
+fn is_maintenance_page(body: &str) -> bool {
+    body.to_ascii_lowercase().contains("maintenance")
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EmailResponse {
@@ -56,13 +335,66 @@ pub struct Response {
     pub refresh_token: String,
 }
 
-#[derive(serde::Deserialize, std::fmt::Debug)]
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Token {
     pub token: String,
     pub expires: u32,
 }
 
-#[derive(serde::Deserialize, std::fmt::Debug)]
+// bad client ids or secrets come back either in the oauth shape or as an error status
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthErrorResponse {
+    pub error: Option<String>,
+    #[serde(rename = "error_description")]
+    pub error_description: Option<String>,
+    pub status: Option<Status>,
+}
+
+impl AuthErrorResponse {
+    // only rejected client credentials, other oauth errors are left to the status code
+    pub fn is_invalid_client(&self) -> bool {
+        self.error
+            .as_deref()
+            .is_some_and(|e| e.eq_ignore_ascii_case("invalid_client"))
+            || self
+                .status
+                .as_ref()
+                .and_then(|s| s.type_field.as_deref())
+                .is_some_and(|t| t.eq_ignore_ascii_case("error"))
+    }
+
+    pub fn message(&self) -> Option<String> {
+        self.error_description
+            .clone()
+            .or_else(|| self.status.as_ref().and_then(|s| s.message.clone()))
+            .or_else(|| self.error.clone())
+    }
+}
+
+impl Debug for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Token")
+            .field("token", &"[redacted]")
+            .field("expires", &self.expires)
+            .finish()
+    }
+}
+
+impl Token {
+    // `expires` is the lifetime in seconds, not a timestamp
+    pub fn expires_in(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.expires.into())
+    }
+
+    pub fn expires_at(&self, received_at: DateTime<Utc>) -> DateTime<Utc> {
+        received_at + Duration::seconds(self.expires.into())
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct TokenResponse {
     pub status: Status,
     pub response: Token,
@@ -90,18 +422,134 @@ pub struct LoginResponse {
     pub response: AccessTokenResponse,
 }
 
-#[derive(serde::Deserialize, std::fmt::Debug)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct RegistrationResponse {
     pub status: Status,
     pub response: AccessTokenResponse,
 }
 
-#[derive(serde::Deserialize, std::fmt::Debug)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ActivationResponse {
     pub status: Status,
     pub response: Option<AccessTokenResponse>,
 }
 
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryResponse {
+    pub status: Status,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryVerificationResponse {
+    pub status: Status,
+    pub response: Option<RecoveryToken>,
+}
+
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryToken {
+    pub recovery_token: String,
+}
+
+impl Debug for RecoveryToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecoveryToken")
+            .field("recovery_token", &"[redacted]")
+            .finish()
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangePasswordResponse {
+    pub status: Status,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteAccountResponse {
+    pub status: Status,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceResponse {
+    pub status: Status,
+    pub response: Option<RegisteredDevice>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceListResponse {
+    pub status: Status,
+    pub response: Option<DeviceList>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceList {
+    pub devices: Vec<RegisteredDevice>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisteredDevice {
+    pub device_id: String,
+    pub device_id_type: Option<String>,
+    pub is_active: Option<String>,
+    pub os: Option<String>,
+    pub os_version: Option<String>,
+    pub timezone: Option<String>,
+    pub registration_date: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PushTokenResponse {
+    pub status: Status,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPreferencesResponse {
+    pub status: Status,
+    pub response: Option<NotificationPreferences>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPreferences {
+    pub preferences: Vec<NotificationPreference>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionsResponse {
+    pub status: Status,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentMethodResponse {
+    pub status: Status,
+    pub response: Option<PaymentMethod>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentMethod {
+    #[serde(rename = "paymentMethodID")]
+    pub payment_method_id: i64,
+    pub payment_type: String,
+    pub nick_name: Option<String>,
+    pub is_preferred: Option<bool>,
+    pub is_expired: Option<bool>,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OfferResponse {
@@ -109,10 +557,17 @@ pub struct OfferResponse {
     pub response: Option<OfferList>,
 }
 
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusResponse {
+    pub status: Status,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Status {
     pub code: Value,
+    #[serde(rename = "type")]
     pub type_field: Option<String>,
     #[serde(rename = "correlationID")]
     pub correlation_id: Option<String>,
@@ -162,6 +617,64 @@ pub struct Offer {
     pub daypart_filters: Vec<Value>,
 }
 
+impl Offer {
+    pub fn language(&self) -> Option<&str> {
+        self.image_base_language.as_deref()
+    }
+
+    pub fn minimum_spend_cents(&self) -> Option<MinimumSpend> {
+        self.conditions.minimum_spend_cents()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MinimumSpend {
+    pub cents: i64,
+    // whether the minimum is compared against the order total before tax
+    pub pre_tax: bool,
+}
+
+impl MinimumSpend {
+    pub fn money(&self, currency: Currency) -> Money {
+        Money::from_cents(self.cents, currency)
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecommendationsResponse {
+    pub status: Status,
+    pub response: Option<RecommendationList>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecommendationList {
+    pub recommendations: Vec<Recommendation>,
+}
+
+// a recommendation points at either an offer or a product
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recommendation {
+    pub recommendation_id: Option<String>,
+    pub offer_proposition_id: Option<i64>,
+    pub product_code: Option<String>,
+    pub name: Option<String>,
+    pub short_description: Option<String>,
+    pub image_base_name: Option<String>,
+    pub reason: Option<String>,
+    pub score: Option<f64>,
+    pub display_order: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OfferImage {
+    pub image_base_name: String,
+    pub content_type: Option<String>,
+    pub bytes: Bytes,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PunchInfo {
@@ -169,6 +682,47 @@ pub struct PunchInfo {
     pub current_punch: i64,
 }
 
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfferLockResponse {
+    pub status: Status,
+    pub response: Option<OfferLock>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OfferLock {
+    pub offer_id: i64,
+    pub is_locked: bool,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PunchCardResponse {
+    pub status: Status,
+    pub response: Option<PunchCard>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PunchCard {
+    pub offer_proposition_id: i64,
+    pub name: Option<String>,
+    pub total_punch: i64,
+    pub current_punch: i64,
+    #[serde(default)]
+    pub history: Vec<PunchHistory>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PunchHistory {
+    #[serde(rename = "punchDateUTC")]
+    pub punch_date_utc: String,
+    pub store_id: Option<String>,
+    pub punches: Option<i64>,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RecurringInfo {
@@ -186,10 +740,91 @@ pub struct RecurringInfo {
 #[serde(rename_all = "camelCase")]
 pub struct Conditions {
     pub day_of_week_conditions: Vec<String>,
-    pub date_conditions: Vec<Value>,
+    pub date_conditions: Vec<DateCondition>,
     pub sale_amount_conditions: Vec<SaleAmountCondition>,
 }
 
+impl Conditions {
+    // days are numbered iso style, 1 is monday and 7 is sunday. values that aren't a valid day
+    // are skipped, and no conditions at all means every day is allowed
+    pub fn allowed_weekdays(&self) -> HashSet<Weekday> {
+        if self.day_of_week_conditions.is_empty() {
+            return WEEKDAYS.into_iter().collect();
+        }
+
+        self.day_of_week_conditions
+            .iter()
+            .filter_map(|day| day.trim().parse::<usize>().ok())
+            .filter_map(|day| day.checked_sub(1).and_then(|i| WEEKDAYS.get(i)))
+            .copied()
+            .collect()
+    }
+
+    pub fn is_allowed_on(&self, weekday: Weekday) -> bool {
+        self.allowed_weekdays().contains(&weekday)
+    }
+
+    // `minimum` is in whole dollars. when there are several conditions, the strictest applies
+    pub fn minimum_spend_cents(&self) -> Option<MinimumSpend> {
+        self.sale_amount_conditions
+            .iter()
+            .filter(|c| c.minimum > 0)
+            .max_by_key(|c| c.minimum)
+            .map(|c| MinimumSpend {
+                cents: c.minimum * 100,
+                pre_tax: c.pre_tax_validation,
+            })
+    }
+
+    // `date` should be local to the restaurant. with several date windows, any one of them is enough
+    pub fn is_valid_on(&self, date: NaiveDate) -> bool {
+        self.is_allowed_on(date.weekday())
+            && (self.date_conditions.is_empty()
+                || self.date_conditions.iter().any(|c| c.contains(date)))
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DateCondition {
+    #[serde(alias = "startDate")]
+    pub begin_date: Option<String>,
+    pub end_date: Option<String>,
+}
+
+impl DateCondition {
+    pub fn begin(&self) -> Option<NaiveDate> {
+        self.begin_date.as_deref().and_then(parse_condition_date)
+    }
+
+    pub fn end(&self) -> Option<NaiveDate> {
+        self.end_date.as_deref().and_then(parse_condition_date)
+    }
+
+    // both ends are inclusive, a missing end leaves that side of the window open
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.begin().is_none_or(|begin| begin <= date) && self.end().is_none_or(|end| date <= end)
+    }
+}
+
+fn parse_condition_date(value: &str) -> Option<NaiveDate> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|d| d.date_naive())
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S").map(|d| d.date()))
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y-%m-%d"))
+        .ok()
+}
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SaleAmountCondition {
@@ -214,6 +849,45 @@ pub struct RestaurantLocationList {
     pub restaurants: Vec<Restaurant>,
 }
 
+impl RestaurantLocationList {
+    // a geojson FeatureCollection with one point per restaurant, coordinates are longitude first
+    pub fn to_geojson(&self) -> Value {
+        let features = self
+            .restaurants
+            .iter()
+            .map(|r| {
+                serde_json::json!({
+                    "type": "Feature",
+                    "geometry": {
+                        "type": "Point",
+                        "coordinates": [r.location.longitude, r.location.latitude],
+                    },
+                    "properties": {
+                        "name": r.name,
+                        "nationalStoreNumber": r.national_store_number,
+                        "address": r.address.address_line1,
+                        "city": r.address.city_town,
+                        "status": r.restaurant_status,
+                        "facilities": r.facilities,
+                    },
+                })
+            })
+            .collect::<Vec<_>>();
+
+        serde_json::json!({
+            "type": "FeatureCollection",
+            "features": features,
+        })
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestaurantLocationCoverage {
+    pub restaurants: Vec<Restaurant>,
+    pub complete: bool,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Restaurant {
@@ -230,6 +904,20 @@ pub struct Restaurant {
     pub phone_number: Option<String>,
 }
 
+impl Restaurant {
+    // `search` is either a national store number or part of the store's name or street address
+    pub fn matches(&self, search: &str) -> bool {
+        let search = search.trim();
+        if let Ok(store_number) = search.parse::<i64>() {
+            return self.national_store_number == store_number;
+        }
+
+        let search = search.to_lowercase();
+        self.name.to_lowercase().contains(&search)
+            || self.address.address_line1.to_lowercase().contains(&search)
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Address {
@@ -311,6 +999,34 @@ pub struct OfferDetails {
     pub daypart_filters: Vec<Value>,
 }
 
+impl OfferDetails {
+    // each product set lists interchangeable products, so a set is satisfied by any one of them.
+    // `store` is the catalog entry for the same restaurant, without it only outages are checked
+    pub fn available_at_store(
+        &self,
+        restaurant: &FullRestaurantInformation,
+        store: Option<&Store>,
+    ) -> bool {
+        let outages = &restaurant.catalog.outage_product_codes;
+        let available = store.map(|s| {
+            s.availability
+                .iter()
+                .map(|a| a.product_code.to_string())
+                .collect::<HashSet<_>>()
+        });
+
+        self.product_sets
+            .iter()
+            .filter(|set| !set.products.is_empty())
+            .all(|set| {
+                set.products.iter().any(|product| {
+                    !outages.contains(product)
+                        && available.as_ref().is_none_or(|a| a.contains(product))
+                })
+            })
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProductSet {
@@ -319,7 +1035,51 @@ pub struct ProductSet {
     pub min_quantity: Option<i64>,
     pub products: Vec<String>,
     pub action: Option<Action>,
-    pub swap_mapping: Vec<Value>,
+    pub swap_mapping: Vec<SwapMapping>,
+}
+
+impl OfferDetails {
+    // true when any of `product_codes` is in one of the offer's product sets, swaps included
+    pub fn covers_any(&self, product_codes: &[String]) -> bool {
+        self.product_sets.iter().any(|set| {
+            set.allowed_products_including_swaps()
+                .into_iter()
+                .any(|product| product_codes.iter().any(|code| code == product))
+        })
+    }
+}
+
+impl ProductSet {
+    // the set's own products first, then whatever they can be swapped for, without duplicates
+    pub fn allowed_products_including_swaps(&self) -> Vec<&str> {
+        let mut allowed = self.products.iter().map(|p| p.as_str()).collect::<Vec<_>>();
+        let swaps = self
+            .swap_mapping
+            .iter()
+            .filter(|m| {
+                m.source_product_code
+                    .as_ref()
+                    .is_none_or(|source| self.products.contains(source))
+            })
+            .flat_map(|m| m.target_product_codes.iter());
+
+        for product in swaps {
+            if !allowed.contains(&product.as_str()) {
+                allowed.push(product);
+            }
+        }
+
+        allowed
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapMapping {
+    #[serde(alias = "productCode", default)]
+    pub source_product_code: Option<String>,
+    #[serde(alias = "swapProductCodes", default)]
+    pub target_product_codes: Vec<String>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -331,6 +1091,70 @@ pub struct Action {
     pub value: f64,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionType {
+    Discount,
+    Other(i64),
+}
+
+impl From<i64> for ActionType {
+    fn from(value: i64) -> Self {
+        match value {
+            1 => Self::Discount,
+            other => Self::Other(other),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscountType {
+    // `value` is the percentage taken off
+    Percentage,
+    // `value` is the amount taken off, in dollars
+    Amount,
+    // `value` replaces the price, in dollars
+    PriceOverride,
+    Other(i64),
+}
+
+impl From<i64> for DiscountType {
+    fn from(value: i64) -> Self {
+        match value {
+            1 => Self::Percentage,
+            2 => Self::Amount,
+            3 => Self::PriceOverride,
+            other => Self::Other(other),
+        }
+    }
+}
+
+impl Action {
+    pub fn action_type(&self) -> ActionType {
+        self.type_field.into()
+    }
+
+    pub fn discount(&self) -> DiscountType {
+        self.discount_type.into()
+    }
+
+    // the price never goes below zero or above the original, unknown discounts leave it as is
+    pub fn apply_to(&self, price: Money) -> Money {
+        let price_cents = price.cents;
+        let discounted = match self.discount() {
+            DiscountType::Percentage => {
+                price_cents.saturating_sub((price_cents as f64 * self.value / 100.0).round() as i64)
+            }
+            DiscountType::Amount => {
+                price_cents.saturating_sub(Money::from_dollars(self.value, price.currency).cents)
+            }
+            DiscountType::PriceOverride => Money::from_dollars(self.value, price.currency).cents,
+            DiscountType::Other(_) => price_cents,
+        };
+
+        Money::from_cents(discounted.clamp(0, price_cents.max(0)), price.currency)
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FrequencyOfferInfo {
@@ -353,33 +1177,236 @@ pub struct OfferDealStack {
     pub deal_stack: Option<Vec<DealStack>>,
 }
 
-#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DealStack {
+    pub offer_id: i64,
+    pub offer_proposition_id: String,
+    pub state: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedemptionHistoryResponse {
+    pub status: Status,
+    pub response: Option<RedemptionHistory>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedemptionHistory {
+    pub redemptions: Vec<Redemption>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Redemption {
+    pub offer_id: i64,
+    pub offer_proposition_id: i64,
+    pub name: Option<String>,
+    #[serde(rename = "redemptionDateUTC")]
+    pub redemption_date_utc: String,
+    pub national_store_number: Option<i64>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoginRefreshResponse {
+    pub response: Option<AccessTokenResponse>,
+    pub status: Status,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogoutResponse {
+    pub status: Status,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomerPointResponse {
+    pub status: Status,
+    pub response: PointInformationResponse,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PointInformationResponse {
+    pub total_points: i64,
+    pub life_time_points: i64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoyaltyIdentificationResponse {
+    pub status: Status,
+    pub response: Option<LoyaltyIdentification>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoyaltyIdentification {
+    pub random_code: String,
+    pub bar_code_content: String,
+    pub expiration_time: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoyaltyRewardsResponse {
+    pub status: Status,
+    pub response: Option<LoyaltyRewardList>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoyaltyRewardList {
+    pub rewards: Vec<LoyaltyReward>,
+}
+
+impl LoyaltyRewardList {
+    // rewards that can be redeemed now come first, most expensive first since that is usually
+    // the best value, then the ones an active offer already covers since the offer saves the
+    // points, followed by the ones closest to being affordable
+    pub fn advise(&self, points: i64, offers: &[OfferDetails]) -> Vec<RedemptionAdvice> {
+        let mut advice = self
+            .rewards
+            .iter()
+            .map(|reward| RedemptionAdvice {
+                reward: reward.clone(),
+                redeemable_now: reward.points <= points,
+                points_short: (reward.points - points).max(0),
+                covered_by_offers: offers
+                    .iter()
+                    .filter(|offer| !offer.is_expired && offer.covers_any(&reward.product_codes))
+                    .map(|offer| offer.offer_proposition_id)
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+
+        advice.sort_by(|a, b| {
+            b.redeemable_now
+                .cmp(&a.redeemable_now)
+                .then_with(|| a.is_covered().cmp(&b.is_covered()))
+                .then_with(|| match a.redeemable_now {
+                    true => b.reward.points.cmp(&a.reward.points),
+                    false => a.points_short.cmp(&b.points_short),
+                })
+        });
+
+        advice
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedemptionAdvice {
+    pub reward: LoyaltyReward,
+    pub redeemable_now: bool,
+    pub points_short: i64,
+    // proposition ids of active offers that include one of the reward's products
+    pub covered_by_offers: Vec<i64>,
+}
+
+impl RedemptionAdvice {
+    // an offer gets the same product without spending points
+    pub fn is_covered(&self) -> bool {
+        !self.covered_by_offers.is_empty()
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoyaltyReward {
+    pub reward_id: i64,
+    pub reward_proposition_id: Option<i64>,
+    pub name: String,
+    pub short_description: Option<String>,
+    pub points: i64,
+    pub tier: Option<String>,
+    #[serde(default)]
+    pub product_codes: Vec<String>,
+    pub image_base_name: Option<String>,
+    #[serde(rename = "validFromUTC")]
+    pub valid_from_utc: Option<String>,
+    #[serde(rename = "validToUTC")]
+    pub valid_to_utc: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoyaltyRedemptionResponse {
+    pub status: Status,
+    pub response: Option<LoyaltyRedemption>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoyaltyRedemption {
+    pub random_code: String,
+    pub bar_code_content: String,
+    pub expiration_time: String,
+    pub offer_id: Option<i64>,
+    pub offer_proposition_id: Option<String>,
+    pub points_deducted: Option<i64>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoyaltyPromotionsResponse {
+    pub status: Status,
+    pub response: Option<LoyaltyPromotionList>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoyaltyPromotionList {
+    pub promotions: Vec<LoyaltyPromotion>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct DealStack {
-    pub offer_id: i64,
-    pub offer_proposition_id: String,
-    pub state: Option<String>,
+pub struct LoyaltyPromotion {
+    pub promotion_id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub points_multiplier: Option<f64>,
+    pub bonus_points: Option<i64>,
+    #[serde(default)]
+    pub product_codes: Vec<String>,
+    #[serde(rename = "validFromUTC")]
+    pub valid_from_utc: Option<String>,
+    #[serde(rename = "validToUTC")]
+    pub valid_to_utc: Option<String>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct LoginRefreshResponse {
-    pub response: Option<AccessTokenResponse>,
+pub struct PromotionsResponse {
     pub status: Status,
+    pub response: Option<PromotionList>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct CustomerPointResponse {
-    pub status: Status,
-    pub response: PointInformationResponse,
+pub struct PromotionList {
+    pub promotions: Vec<Promotion>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct PointInformationResponse {
-    pub total_points: i64,
-    pub life_time_points: i64,
+pub struct Promotion {
+    pub promotion_id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub promotional_label: Option<String>,
+    pub image_base_name: Option<String>,
+    #[serde(default)]
+    pub product_codes: Vec<String>,
+    #[serde(rename = "validFromUTC")]
+    pub valid_from_utc: Option<String>,
+    #[serde(rename = "validToUTC")]
+    pub valid_to_utc: Option<String>,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -401,7 +1428,7 @@ pub struct Market {
     #[serde(rename = "DisplayCategoryVersion")]
     pub display_category_version: Value,
     #[serde(rename = "DisplayCategory")]
-    pub display_category: Value,
+    pub display_category: Vec<DisplayCategory>,
     #[serde(rename = "FacilityVersion")]
     pub facility_version: Value,
     #[serde(rename = "Facilities")]
@@ -456,6 +1483,92 @@ pub struct Market {
     pub customer_enums: Value,
 }
 
+impl Market {
+    // the catalog carries the same static data as get_market_static_data, empty slots are
+    // treated as empty lists
+    pub fn static_data(&self) -> Result<MarketStaticData, ClientError> {
+        fn list<T: serde::de::DeserializeOwned>(
+            value: &Value,
+        ) -> Result<Vec<T>, serde_json::Error> {
+            match value {
+                Value::Null => Ok(Vec::new()),
+                value => serde_json::from_value(value.clone()),
+            }
+        }
+
+        Ok(MarketStaticData {
+            tender_types: list(&self.tender_types)?,
+            facilities: list(&self.facilities)?,
+            feedback_types: list(&self.feedback_type_names)?,
+        })
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketStaticDataResponse {
+    pub status: Status,
+    pub response: Option<MarketStaticData>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketStaticData {
+    #[serde(rename = "TenderTypes", default)]
+    pub tender_types: Vec<TenderType>,
+    #[serde(rename = "Facilities", default)]
+    pub facilities: Vec<Facility>,
+    #[serde(rename = "FeedbackTypeNames", default)]
+    pub feedback_types: Vec<FeedbackType>,
+}
+
+impl MarketStaticData {
+    pub fn tender_type(&self, id: i64) -> Option<&TenderType> {
+        self.tender_types.iter().find(|t| t.tender_type_id == id)
+    }
+
+    pub fn facility(&self, id: i64) -> Option<&Facility> {
+        self.facilities.iter().find(|f| f.facility_id == id)
+    }
+
+    pub fn feedback_type(&self, id: i64) -> Option<&FeedbackType> {
+        self.feedback_types
+            .iter()
+            .find(|f| f.feedback_type_id == id)
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TenderType {
+    #[serde(rename = "TenderTypeID")]
+    pub tender_type_id: i64,
+    #[serde(rename = "TenderTypeCode")]
+    pub tender_type_code: Option<String>,
+    #[serde(rename = "Name")]
+    pub name: String,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Facility {
+    #[serde(rename = "FacilityID")]
+    pub facility_id: i64,
+    #[serde(rename = "FacilityCode")]
+    pub facility_code: Option<String>,
+    #[serde(rename = "Names", default)]
+    pub names: Vec<Name>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedbackType {
+    #[serde(rename = "FeedbackTypeID")]
+    pub feedback_type_id: i64,
+    #[serde(rename = "Names", default)]
+    pub names: Vec<Name>,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Store {
@@ -660,6 +1773,98 @@ pub struct Nutrition {
     pub disclaimer_ids: Value,
 }
 
+const KILOJOULES_PER_KILOCALORIE: f64 = 4.184;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnergyUnit {
+    Kilojoules,
+    Kilocalories,
+}
+
+impl EnergyUnit {
+    pub fn parse(unit: &str) -> Option<Self> {
+        match unit.trim().to_ascii_lowercase().as_str() {
+            "kj" | "kilojoules" => Some(Self::Kilojoules),
+            "kcal" | "cal" | "calories" | "kilocalories" => Some(Self::Kilocalories),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for EnergyUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnergyUnit::Kilojoules => write!(f, "kJ"),
+            EnergyUnit::Kilocalories => write!(f, "kcal"),
+        }
+    }
+}
+
+impl Nutrition {
+    // `energy` is always reported in kJ, `KCal` is sometimes filled in by the market
+    pub fn energy_in(&self, unit: EnergyUnit) -> f64 {
+        match unit {
+            EnergyUnit::Kilojoules => self.energy as f64,
+            EnergyUnit::Kilocalories => number_from_value(&self.kcal)
+                .unwrap_or(self.energy as f64 / KILOJOULES_PER_KILOCALORIE),
+        }
+    }
+
+    pub fn energy_for(&self, restaurant: &RestaurantNutrition) -> Option<(f64, EnergyUnit)> {
+        let unit = restaurant.unit()?;
+        Some((self.energy_in(unit), unit))
+    }
+
+    // markets send these as numbers, numeric strings or empty strings, so missing and
+    // unparseable values are both None
+    pub fn facts(&self) -> NutritionFacts {
+        NutritionFacts {
+            energy_kj: self.energy as f64,
+            energy_kcal: self.energy_in(EnergyUnit::Kilocalories),
+            total_fat: number_from_value(&self.totalfat),
+            saturated_fat: number_from_value(&self.saturatedfat),
+            trans_fat: number_from_value(&self.transfat),
+            cholesterol: number_from_value(&self.cholesterol),
+            sodium: number_from_value(&self.sodium),
+            carbohydrates: number_from_value(&self.carbohydrates),
+            dietary_fiber: number_from_value(&self.dietaryfiber),
+            sugars: number_from_value(&self.sugars),
+            protein: number_from_value(&self.protein),
+        }
+    }
+}
+
+fn number_from_value(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse::<f64>().ok(),
+        _ => None,
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NutritionFacts {
+    pub energy_kj: f64,
+    pub energy_kcal: f64,
+    pub total_fat: Option<f64>,
+    pub saturated_fat: Option<f64>,
+    pub trans_fat: Option<f64>,
+    pub cholesterol: Option<f64>,
+    pub sodium: Option<f64>,
+    pub carbohydrates: Option<f64>,
+    pub dietary_fiber: Option<f64>,
+    pub sugars: Option<f64>,
+    pub protein: Option<f64>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NutritionResponse {
+    pub status: Status,
+    pub response: Option<Nutrition>,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Category {
@@ -671,6 +1876,50 @@ pub struct Category {
     pub display_size_selection: i64,
 }
 
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayCategoriesResponse {
+    pub status: Status,
+    #[serde(rename = "DisplayCategoryVersion")]
+    pub display_category_version: Value,
+    #[serde(rename = "DisplayCategory")]
+    pub display_category: Vec<DisplayCategory>,
+}
+
+impl DisplayCategoriesResponse {
+    // top level categories have no parent, results are sorted by display order
+    pub fn children_of(&self, parent_id: Option<i64>) -> Vec<&DisplayCategory> {
+        let mut children = self
+            .display_category
+            .iter()
+            .filter(|c| c.parent_display_category_id.filter(|id| *id != 0) == parent_id)
+            .collect::<Vec<_>>();
+        children.sort_by_key(|c| c.display_order);
+        children
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayCategory {
+    #[serde(rename = "DisplayCategoryID")]
+    pub display_category_id: i64,
+    #[serde(rename = "ParentDisplayCategoryID")]
+    pub parent_display_category_id: Option<i64>,
+    #[serde(rename = "DisplayOrder")]
+    pub display_order: i64,
+    #[serde(rename = "Names")]
+    pub names: Vec<Name>,
+}
+
+impl DisplayCategory {
+    pub fn name_for(&self, language_id: &str) -> Option<&Name> {
+        self.names
+            .iter()
+            .find(|n| n.language_id.eq_ignore_ascii_case(language_id))
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Dimension {
@@ -848,6 +2097,14 @@ pub struct Names {
     pub names: Vec<Name>,
 }
 
+impl Names {
+    pub fn name_for(&self, language_id: &str) -> Option<&Name> {
+        self.names
+            .iter()
+            .find(|n| n.language_id.eq_ignore_ascii_case(language_id))
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Name {
@@ -896,6 +2153,12 @@ pub struct Price {
     pub is_valid: bool,
 }
 
+impl Price {
+    pub fn money(&self, currency: Currency) -> Money {
+        Money::from_dollars(self.price, currency)
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Availability {
@@ -903,6 +2166,27 @@ pub struct Availability {
     pub product_code: i64,
 }
 
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductResponse {
+    pub status: Status,
+    pub response: Option<ProductDetail>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductDetail {
+    #[serde(rename = "Product")]
+    pub product: Product,
+    #[serde(rename = "Prices")]
+    #[serde(default)]
+    pub prices: Vec<Price>,
+    #[serde(rename = "Recipe")]
+    pub recipe: Option<Recipe>,
+    #[serde(rename = "Names")]
+    pub names: Option<Names>,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RestaurantResponse {
@@ -959,6 +2243,22 @@ pub struct FullRestaurantInformation {
     pub available_menu_products: Option<AvailableMenuProducts>,
 }
 
+impl FullRestaurantInformation {
+    pub fn max_stackable_offers(&self) -> Option<usize> {
+        let config = self.offer_configuration.as_ref()?;
+        if !config.enable_multiple_offers {
+            return Some(1);
+        }
+
+        let limit: i64 = config.offer_buckets.iter().map(|b| b.limit.max(0)).sum();
+        if limit > 0 {
+            Some(limit as usize)
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Catalog {
@@ -967,6 +2267,25 @@ pub struct Catalog {
     pub outage_product_codes: Vec<String>,
 }
 
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductOutagesResponse {
+    pub status: Status,
+    pub response: Option<ProductOutages>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProductOutages {
+    pub outage_product_codes: Vec<String>,
+}
+
+impl ProductOutages {
+    pub fn is_out(&self, product_code: &str) -> bool {
+        self.outage_product_codes.iter().any(|p| p == product_code)
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PointsOfDistribution {
@@ -1006,6 +2325,19 @@ pub struct TableService {
     pub table_service_table_number_max_number_value: i64,
 }
 
+impl TableService {
+    // stores using table locators number them separately from their tables
+    pub fn table_number_range(&self) -> RangeInclusive<i64> {
+        if self.table_service_locator_enabled {
+            self.table_service_locator_min_number_value
+                ..=self.table_service_locator_max_number_value
+        } else {
+            self.table_service_table_number_min_number_value
+                ..=self.table_service_table_number_max_number_value
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Order {
@@ -1033,6 +2365,79 @@ pub struct AutoBagSaleInformation {
     pub no_bag_product_code: i64,
 }
 
+impl Order {
+    // slots are `interval_minutes` apart within the store's menu calendar for `date`, and only
+    // those between the minimum and maximum lead times after `now` are returned. `now` and the
+    // slots are local to the store, calendar week days are numbered 1 for monday to 7 for sunday
+    pub fn pickup_slots(
+        &self,
+        date: NaiveDate,
+        now: NaiveDateTime,
+        interval_minutes: i64,
+    ) -> Vec<NaiveDateTime> {
+        // lead times come from the api, any that are out of range give no slots
+        let add_minutes = |time: NaiveDateTime, minutes: i64| {
+            Duration::try_minutes(minutes).and_then(|d| time.checked_add_signed(d))
+        };
+
+        let Some(interval) = Duration::try_minutes(interval_minutes.max(1)) else {
+            return Vec::new();
+        };
+        let Some(earliest) = add_minutes(now, self.minimum_time_minutes.unwrap_or(0)) else {
+            return Vec::new();
+        };
+        let latest = match self.maximum_time_minutes {
+            Some(minutes) => match add_minutes(now, minutes) {
+                Some(latest) => Some(latest),
+                None => return Vec::new(),
+            },
+            None => None,
+        };
+        let week_day = date.weekday().number_from_monday() as i64;
+
+        let mut slots = BTreeSet::new();
+        for calendar in self
+            .store_menu_type_calendar
+            .iter()
+            .filter(|c| c.week_day == week_day)
+        {
+            let (Some(start), Some(end)) = (
+                parse_calendar_time(&calendar.start_time),
+                parse_calendar_time(&calendar.end_time),
+            ) else {
+                continue;
+            };
+
+            let start = date.and_time(start);
+            // calendars that run past midnight end on the next day
+            let end = match date.and_time(end) {
+                end if end <= start => match end.checked_add_signed(Duration::days(1)) {
+                    Some(end) => end,
+                    None => continue,
+                },
+                end => end,
+            };
+
+            let mut slot = Some(start);
+            while let Some(current) = slot.filter(|s| *s < end) {
+                if current >= earliest && latest.is_none_or(|latest| current <= latest) {
+                    slots.insert(current);
+                }
+
+                slot = current.checked_add_signed(interval);
+            }
+        }
+
+        slots.into_iter().collect()
+    }
+}
+
+fn parse_calendar_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(value, "%H:%M"))
+        .ok()
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StoreMenuTypeCalendar {
@@ -1065,6 +2470,12 @@ pub struct RestaurantNutrition {
     pub recalculate_energy_on_grill: bool,
 }
 
+impl RestaurantNutrition {
+    pub fn unit(&self) -> Option<EnergyUnit> {
+        EnergyUnit::parse(&self.energy_unit)
+    }
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OfferConfiguration {
@@ -1111,3 +2522,213 @@ pub struct AvailableMenuProducts {
     #[serde(rename = "3")]
     pub n3: Vec<i64>,
 }
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderTotalResponse {
+    pub status: Status,
+    pub response: Option<OrderTotal>,
+}
+
+// amounts are in the market's currency, e.g. dollars
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderTotal {
+    pub subtotal: f64,
+    pub tax: f64,
+    #[serde(default)]
+    pub discount: f64,
+    pub total: f64,
+    #[serde(default)]
+    pub items: Vec<PricedItem>,
+    #[serde(default)]
+    pub discounts: Vec<AppliedDiscount>,
+}
+
+impl OrderTotal {
+    pub fn subtotal_money(&self, currency: Currency) -> Money {
+        Money::from_dollars(self.subtotal, currency)
+    }
+
+    pub fn tax_money(&self, currency: Currency) -> Money {
+        Money::from_dollars(self.tax, currency)
+    }
+
+    pub fn discount_money(&self, currency: Currency) -> Money {
+        Money::from_dollars(self.discount, currency)
+    }
+
+    pub fn total_money(&self, currency: Currency) -> Money {
+        Money::from_dollars(self.total, currency)
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PricedItem {
+    pub product_code: String,
+    pub quantity: i64,
+    pub unit_price: f64,
+    pub total_price: f64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedDiscount {
+    pub offer_id: Option<i64>,
+    pub offer_proposition_id: Option<String>,
+    pub amount: f64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderResponse {
+    pub status: Status,
+    pub response: Option<PlacedOrder>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlacedOrder {
+    pub order_id: String,
+    pub order_number: Option<String>,
+    pub order_status: Option<OrderState>,
+    pub store_id: Option<String>,
+    pub pickup_type: Option<String>,
+    pub total: Option<OrderTotal>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderStatusResponse {
+    pub status: Status,
+    pub response: Option<OrderStatus>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderStatus {
+    pub order_id: String,
+    pub order_status: OrderState,
+    #[serde(rename = "lastUpdatedUTC")]
+    pub last_updated_utc: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OrderState {
+    Placed,
+    Accepted,
+    Preparing,
+    Ready,
+    Completed,
+    Cancelled,
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+impl OrderState {
+    // no further changes are expected once an order gets here
+    pub fn is_final(&self) -> bool {
+        matches!(self, OrderState::Completed | OrderState::Cancelled)
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiptResponse {
+    pub status: Status,
+    pub response: Option<Receipt>,
+}
+
+// amounts are in the market's currency, e.g. dollars
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Receipt {
+    pub order_id: String,
+    pub order_number: Option<String>,
+    pub store_id: Option<String>,
+    pub store_name: Option<String>,
+    // the business number printed on tax invoices, e.g. an abn
+    pub tax_id: Option<String>,
+    #[serde(rename = "issuedAtUTC")]
+    pub issued_at_utc: Option<String>,
+    #[serde(default)]
+    pub line_items: Vec<ReceiptLineItem>,
+    pub subtotal: f64,
+    pub tax: f64,
+    #[serde(default)]
+    pub discount: f64,
+    pub total: f64,
+    pub payment_method: Option<String>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiptLineItem {
+    pub product_code: String,
+    pub name: String,
+    pub quantity: i64,
+    pub unit_price: f64,
+    pub total_price: f64,
+    #[serde(default)]
+    pub tax: f64,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryTrackingResponse {
+    pub status: Status,
+    pub response: Option<DeliveryTracking>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryTracking {
+    pub order_id: String,
+    pub delivery_status: DeliveryState,
+    pub courier: Option<Courier>,
+    #[serde(rename = "estimatedDeliveryTimeUTC")]
+    pub estimated_delivery_time_utc: Option<String>,
+    pub eta_minutes: Option<i64>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Courier {
+    pub name: Option<String>,
+    pub vehicle_type: Option<String>,
+    pub location: Option<Location>,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DeliveryState {
+    Searching,
+    CourierAssigned,
+    PickedUp,
+    Delivered,
+    Cancelled,
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryEstimateResponse {
+    pub status: Status,
+    pub response: Option<DeliveryEstimate>,
+}
+
+// amounts are in the market's currency, e.g. dollars
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryEstimate {
+    pub available: bool,
+    pub store_id: Option<String>,
+    pub delivery_fee: Option<f64>,
+    pub minimum_order_value: Option<f64>,
+    pub estimated_delivery_minutes: Option<i64>,
+}